@@ -0,0 +1,242 @@
+//! Glossary page
+//!
+//! GLOSSARY.md (`structure.glossary`) is rendered as GLOSSARY.html: a section
+//! per term with its definition and the pages that use it. Glossary terms
+//! marked up in pages link to their section, and their definitions are added
+//! to the page as hidden popovers that gitbook.js shows on hover and focus.
+
+use crate::builder::plugins::PageInfo;
+use crate::builder::renderer::{self, render_markdown_fragment, TocItem};
+use crate::builder::{UiStrings, LINK_ATTRIBUTE};
+use crate::parser::glossary::{apply_glossary, definition_id};
+use crate::parser::{BookConfig, Glossary};
+use regex::Captures;
+use std::collections::HashMap;
+
+/// A page that uses a term
+#[derive(Debug, Clone)]
+struct Reference {
+    /// Source file, so README.md is listed once as a chapter and as index.html
+    source: String,
+    html_path: String,
+    title: String,
+}
+
+/// The glossary of a book, with the pages each term appears on
+pub struct GlossaryPage<'a> {
+    pub glossary: &'a Glossary,
+    /// Output path of the page (e.g. "GLOSSARY.html")
+    pub html_path: String,
+    /// Rendered definitions in `glossary.terms` order, with links relative
+    /// to the glossary page
+    definitions: Vec<String>,
+    /// Term → pages using it, in build order
+    references: HashMap<String, Vec<Reference>>,
+}
+
+impl<'a> GlossaryPage<'a> {
+    pub fn new(glossary: &'a Glossary, config: &BookConfig) -> Self {
+        let definitions = glossary
+            .terms
+            .iter()
+            .map(|term| render_markdown_fragment(&term.markdown, config.hardbreaks, &format!("{}-", definition_id(term))))
+            .collect();
+        Self {
+            glossary,
+            html_path: config.structure.glossary.replace(".md", ".html"),
+            definitions,
+            references: HashMap::new(),
+        }
+    }
+
+    /// Mark up the glossary terms of a page, linking them to their entries,
+    /// and record the page under each term it uses. A SUMMARY.md link to
+    /// the glossary itself is replaced by the generated page, and a page
+    /// can opt out with `glossary: false` in its front matter.
+    ///
+    /// Returns the page HTML and the names of the terms it uses
+    pub fn apply(&mut self, html: &str, page: &PageInfo, config: &BookConfig) -> (String, Vec<String>) {
+        let html_path = page.output;
+        let opted_out = page.front_matter.and_then(|fm| fm.glossary) == Some(false);
+        if self.glossary.is_empty() || html_path == self.html_path || opted_out {
+            return (html.to_string(), Vec::new());
+        }
+        let link = format!("{}{}", root_prefix(html_path), self.html_path);
+        let markup = apply_glossary(html, self.glossary, &config.glossary, Some(&link));
+        for term in &markup.terms {
+            let references = self.references.entry(term.clone()).or_default();
+            if !references.iter().any(|r| r.source == page.path) {
+                references.push(Reference {
+                    source: page.path.to_string(),
+                    html_path: html_path.to_string(),
+                    title: page.title.to_string(),
+                });
+            }
+        }
+        (markup.html, markup.terms)
+    }
+
+    /// Hidden definitions of the terms a page uses, referenced by the
+    /// `aria-describedby` of its marked up terms
+    pub fn popovers(&self, terms: &[String], html_path: &str) -> String {
+        if terms.is_empty() {
+            return String::new();
+        }
+        // Links in definitions are relative to the glossary page
+        let base = match self.html_path.rfind('/') {
+            Some(slash) => format!("{}{}", root_prefix(html_path), &self.html_path[..=slash]),
+            None => root_prefix(html_path),
+        };
+
+        let mut html = String::from("<div class=\"glossary-popovers\">\n");
+        for name in terms {
+            let Some(idx) = self.glossary.terms.iter().position(|term| &term.name == name) else {
+                continue;
+            };
+            html.push_str(&format!(
+                "<div id=\"{}\" class=\"glossary-popover\" hidden>\n{}</div>\n",
+                renderer::html_escape(&definition_id(&self.glossary.terms[idx])),
+                relative_to(&self.definitions[idx], &base)
+            ));
+        }
+        html.push_str("</div>\n");
+        html
+    }
+
+    /// Page title: the # heading of GLOSSARY.md, or "Glossary" in the book language
+    pub fn title(&self, strings: &UiStrings) -> String {
+        self.glossary
+            .title
+            .clone()
+            .unwrap_or_else(|| strings.get("glossary").to_string())
+    }
+
+    /// Body of the page and its headings, one h2 per term
+    pub fn render(&self, config: &BookConfig) -> (String, Vec<TocItem>) {
+        let strings = UiStrings::for_config(config);
+        let prefix = root_prefix(&self.html_path);
+        let mut html = format!("<h1>{}</h1>\n<div class=\"glossary\">\n", renderer::html_escape(&self.title(&strings)));
+        let mut headings = Vec::new();
+
+        for (term, definition) in self.glossary.terms.iter().zip(&self.definitions) {
+            html.push_str(&format!(
+                "<h2 id=\"{}\">{}</h2>\n",
+                renderer::html_escape(&term.anchor),
+                renderer::html_escape(&term.name)
+            ));
+            html.push_str(definition);
+
+            if let Some(references) = self.references.get(&term.name) {
+                let label = strings.get("glossary_references").replace("{term}", &term.name);
+                html.push_str(&format!(
+                    "<nav class=\"glossary-references\" aria-label=\"{}\">\n<p>{}</p>\n<ul>\n",
+                    renderer::html_escape(&label),
+                    renderer::html_escape(strings.get("glossary_used_in"))
+                ));
+                for reference in references {
+                    let title = if reference.title.is_empty() { &reference.html_path } else { &reference.title };
+                    html.push_str(&format!(
+                        "<li><a href=\"{}{}\">{}</a></li>\n",
+                        prefix,
+                        renderer::html_escape(&reference.html_path),
+                        renderer::html_escape(title)
+                    ));
+                }
+                html.push_str("</ul>\n</nav>\n");
+            }
+
+            headings.push(TocItem {
+                level: 2,
+                text: term.name.clone(),
+                id: term.anchor.clone(),
+            });
+        }
+
+        html.push_str("</div>\n");
+        (html, headings)
+    }
+}
+
+/// Prefix the relative `href` and `src` URLs of HTML with `base`
+fn relative_to(html: &str, base: &str) -> String {
+    if base.is_empty() {
+        return html.to_string();
+    }
+    LINK_ATTRIBUTE.replace_all(html, |caps: &Captures| {
+        let url = &caps[2];
+        let absolute = url.is_empty()
+            || url.starts_with(['#', '/'])
+            || url.split('/').next().is_some_and(|first| first.contains(':'));
+        if absolute {
+            caps[0].to_string()
+        } else {
+            format!("{}=\"{}{}\"", &caps[1], base, url)
+        }
+    })
+    .to_string()
+}
+
+/// Relative path from a page to the output root ("" or "../../")
+fn root_prefix(html_path: &str) -> String {
+    "../".repeat(html_path.matches('/').count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::FrontMatter;
+
+    #[test]
+    fn test_glossary_page_references() {
+        let glossary = Glossary::parse("# Terms\n\n## API\nApplication *Programming* Interface, see [calls](guide/calls.md)\n\n## SDK\nKit\n").unwrap();
+        let config = BookConfig::default();
+        let mut page = GlossaryPage::new(&glossary, &config);
+
+        let page_info = |path, output, title| PageInfo { path, output, title, front_matter: None };
+        let (html, terms) = page.apply("<p>The API.</p>", &page_info("guide/calls.md", "guide/calls.html", "Calls"), &config);
+        assert_eq!(
+            html,
+            r#"<p>The <a class="glossary-term" href="../GLOSSARY.html#api" aria-describedby="glossary-def-api">API</a>.</p>"#
+        );
+        let popovers = page.popovers(&terms, "guide/calls.html");
+        assert!(popovers.contains("<div id=\"glossary-def-api\" class=\"glossary-popover\" hidden>\n<p>Application <em>Programming</em> Interface, see <a href=\"../guide/calls.html\">calls</a></p>"));
+        assert!(!popovers.contains("glossary-def-sdk"));
+        page.apply("<p>API and API</p>", &page_info("README.md", "README.html", "Home"), &config);
+        page.apply("<p>API again</p>", &page_info("README.md", "index.html", "Home"), &config);
+        let opt_out = FrontMatter { glossary: Some(false), ..Default::default() };
+        let page_info_opt_out = PageInfo { front_matter: Some(&opt_out), ..page_info("faq.md", "faq.html", "FAQ") };
+        let (html, terms) = page.apply("<p>API</p>", &page_info_opt_out, &config);
+        assert_eq!(html, "<p>API</p>");
+        assert!(terms.is_empty());
+
+        let (html, headings) = page.render(&config);
+        assert!(html.starts_with("<h1>Terms</h1>\n<div class=\"glossary\">\n<h2 id=\"api\">API</h2>\n"));
+        assert!(html.contains("<em>Programming</em>"));
+        assert!(html.contains(
+            "<nav class=\"glossary-references\" aria-label=\"Pages using API\">\n<p>Used in</p>\n<ul>\n<li><a href=\"guide/calls.html\">Calls</a></li>\n<li><a href=\"README.html\">Home</a></li>\n</ul>\n</nav>\n<h2 id=\"sdk\""
+        ));
+        // SDK isn't used anywhere
+        assert!(html.ends_with("<p>Kit</p>\n</div>\n"));
+        assert_eq!(headings.len(), 2);
+        assert_eq!(headings[1].id, "sdk");
+    }
+
+    #[test]
+    fn test_definition_tab_ids() {
+        let glossary = Glossary::parse("## CLI
+::: tabs
+== Linux
+sh
+== Windows
+cmd
+:::
+").unwrap();
+        let config = BookConfig::default();
+        let page = GlossaryPage::new(&glossary, &config);
+
+        // Tab sets of definitions don't share ids with the tab sets of the page
+        let popovers = page.popovers(&["CLI".to_string()], "guide/setup.html");
+        assert!(popovers.contains(r#"id="glossary-def-cli-tabs-1-0" aria-controls="glossary-def-cli-tabs-1-0-panel""#), "{}", popovers);
+        assert!(!popovers.contains(r#"id="tabs-1-0""#), "{}", popovers);
+    }
+}
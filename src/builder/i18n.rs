@@ -0,0 +1,231 @@
+//! Localized strings for the theme chrome (search box, toolbars, navigation)
+//!
+//! Built-in translations are keyed by language code. Books can override any
+//! string per language in book.json:
+//! ```json
+//! {
+//!     "i18n": {
+//!         "ja": { "search_placeholder": "キーワードを入力" }
+//!     }
+//! }
+//! ```
+
+use crate::parser::BookConfig;
+use std::collections::HashMap;
+
+/// English strings, also used for any key a translation doesn't define
+const EN: &[(&str, &str)] = &[
+    ("search_placeholder", "Search..."),
+    ("search_label", "Search"),
+    ("search_no_results", "No results found"),
+    ("toggle_sidebar", "Toggle Sidebar"),
+    ("toggle_toc", "Toggle Table of Contents"),
+    ("toc_header", "On This Page"),
+    ("font_settings", "Font Settings"),
+    ("font_decrease", "Decrease font size"),
+    ("font_increase", "Increase font size"),
+    ("theme_white", "White theme"),
+    ("theme_sepia", "Sepia theme"),
+    ("theme_night", "Night theme"),
+    ("theme_auto", "Match system theme"),
+    ("previous_page", "Previous page"),
+    ("next_page", "Next page"),
+    ("back_to_top", "Back to top"),
+    ("choose_language", "Choose a language"),
+    ("glossary", "Glossary"),
+    ("glossary_used_in", "Used in"),
+    ("glossary_references", "Pages using {term}"),
+    ("callout_note", "Note"),
+    ("callout_tip", "Tip"),
+    ("callout_important", "Important"),
+    ("callout_warning", "Warning"),
+    ("callout_caution", "Caution"),
+    ("callout_danger", "Danger"),
+    ("untranslated_notice", "This page has not been translated yet. It is shown in {language}."),
+];
+
+const JA: &[(&str, &str)] = &[
+    ("search_placeholder", "検索..."),
+    ("search_label", "検索"),
+    ("search_no_results", "該当する結果がありません"),
+    ("toggle_sidebar", "サイドバーの表示切替"),
+    ("toggle_toc", "目次の表示切替"),
+    ("toc_header", "このページの内容"),
+    ("font_settings", "フォント設定"),
+    ("font_decrease", "文字を小さく"),
+    ("font_increase", "文字を大きく"),
+    ("theme_white", "ホワイトテーマ"),
+    ("theme_sepia", "セピアテーマ"),
+    ("theme_night", "ナイトテーマ"),
+    ("theme_auto", "システムの設定に合わせる"),
+    ("previous_page", "前のページ"),
+    ("next_page", "次のページ"),
+    ("back_to_top", "トップに戻る"),
+    ("choose_language", "言語を選択"),
+    ("glossary", "用語集"),
+    ("glossary_used_in", "使用しているページ"),
+    ("glossary_references", "「{term}」を使用しているページ"),
+    ("callout_note", "注記"),
+    ("callout_tip", "ヒント"),
+    ("callout_important", "重要"),
+    ("callout_warning", "警告"),
+    ("callout_caution", "注意"),
+    ("callout_danger", "危険"),
+    ("untranslated_notice", "このページはまだ翻訳されていません。{language}版を表示しています。"),
+];
+
+const VI: &[(&str, &str)] = &[
+    ("search_placeholder", "Tìm kiếm..."),
+    ("search_label", "Tìm kiếm"),
+    ("search_no_results", "Không tìm thấy kết quả"),
+    ("toggle_sidebar", "Ẩn/hiện thanh bên"),
+    ("toggle_toc", "Ẩn/hiện mục lục"),
+    ("toc_header", "Trong trang này"),
+    ("font_settings", "Cài đặt phông chữ"),
+    ("font_decrease", "Giảm cỡ chữ"),
+    ("font_increase", "Tăng cỡ chữ"),
+    ("theme_white", "Giao diện sáng"),
+    ("theme_sepia", "Giao diện sepia"),
+    ("theme_night", "Giao diện tối"),
+    ("theme_auto", "Theo cài đặt hệ thống"),
+    ("previous_page", "Trang trước"),
+    ("next_page", "Trang sau"),
+    ("back_to_top", "Lên đầu trang"),
+    ("choose_language", "Chọn ngôn ngữ"),
+    ("glossary", "Thuật ngữ"),
+    ("glossary_used_in", "Xuất hiện trong"),
+    ("glossary_references", "Các trang dùng {term}"),
+    ("callout_note", "Ghi chú"),
+    ("callout_tip", "Mẹo"),
+    ("callout_important", "Quan trọng"),
+    ("callout_warning", "Cảnh báo"),
+    ("callout_caution", "Thận trọng"),
+    ("callout_danger", "Nguy hiểm"),
+    ("untranslated_notice", "Trang này chưa được dịch. Đang hiển thị bản {language}."),
+];
+
+/// Get the built-in string table for a language code (e.g. "ja", "ja-JP")
+fn builtin_strings(lang: &str) -> Option<&'static [(&'static str, &'static str)]> {
+    let primary = lang.split(['-', '_']).next().unwrap_or("").to_lowercase();
+    match primary.as_str() {
+        "en" => Some(EN),
+        "ja" | "jp" => Some(JA),
+        "vi" | "vn" => Some(VI),
+        _ => None,
+    }
+}
+
+/// UI strings resolved for one book language
+#[derive(Debug, Clone)]
+pub struct UiStrings {
+    strings: HashMap<String, String>,
+}
+
+impl UiStrings {
+    /// Resolve strings for the book language: English defaults, then the
+    /// built-in translation, then book.json overrides
+    pub fn for_config(config: &BookConfig) -> Self {
+        let lang = config.html_lang();
+        let mut strings: HashMap<String, String> = EN
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        if let Some(table) = builtin_strings(lang) {
+            for (key, value) in table {
+                strings.insert(key.to_string(), value.to_string());
+            }
+        }
+
+        // Overrides: primary subtag first, so an exact code ("pt-BR") wins over "pt"
+        let primary = lang.split(['-', '_']).next().unwrap_or(lang);
+        let mut codes = vec![primary];
+        if lang != primary {
+            codes.push(lang);
+        }
+        for code in codes {
+            if let Some(overrides) = config.i18n.get(code) {
+                for (key, value) in overrides {
+                    strings.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        Self { strings }
+    }
+
+    /// Get a string by key, or the key itself if unknown
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings.get(key).map(String::as_str).unwrap_or(key)
+    }
+
+    /// All strings, for use as a template context value
+    pub fn as_map(&self) -> &HashMap<String, String> {
+        &self.strings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_from(json: &str) -> BookConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_translation() {
+        let strings = UiStrings::for_config(&config_from(r#"{"language": "ja"}"#));
+        assert_eq!(strings.get("search_placeholder"), "検索...");
+
+        let strings = UiStrings::for_config(&config_from(r#"{"language": "vi"}"#));
+        assert_eq!(strings.get("next_page"), "Trang sau");
+    }
+
+    #[test]
+    fn test_region_subtag_uses_primary_language() {
+        let strings = UiStrings::for_config(&config_from(r#"{"language": "ja-JP"}"#));
+        assert_eq!(strings.get("toc_header"), "このページの内容");
+    }
+
+    #[test]
+    fn test_unknown_language_falls_back_to_english() {
+        let strings = UiStrings::for_config(&config_from(r#"{"language": "de"}"#));
+        assert_eq!(strings.get("search_placeholder"), "Search...");
+    }
+
+    #[test]
+    fn test_book_json_overrides() {
+        let config = config_from(r#"{
+            "language": "de",
+            "i18n": {
+                "de": {"search_placeholder": "Suchen...", "next_page": "Weiter"},
+                "ja": {"next_page": "次へ"}
+            }
+        }"#);
+        let strings = UiStrings::for_config(&config);
+        assert_eq!(strings.get("search_placeholder"), "Suchen...");
+        assert_eq!(strings.get("next_page"), "Weiter");
+        // Keys without an override keep the English default
+        assert_eq!(strings.get("back_to_top"), "Back to top");
+    }
+
+    #[test]
+    fn test_exact_code_override_wins() {
+        let config = config_from(r#"{
+            "language": "pt-BR",
+            "i18n": {
+                "pt": {"next_page": "Seguinte"},
+                "pt-BR": {"next_page": "Próxima"}
+            }
+        }"#);
+        let strings = UiStrings::for_config(&config);
+        assert_eq!(strings.get("next_page"), "Próxima");
+    }
+
+    #[test]
+    fn test_unknown_key() {
+        let strings = UiStrings::for_config(&BookConfig::default());
+        assert_eq!(strings.get("no_such_key"), "no_such_key");
+    }
+}
@@ -0,0 +1,317 @@
+//! Translation coverage report for multi-language books (`guidebook i18n-status`)
+//!
+//! Every language is compared against a reference language: pages missing from
+//! the translation, pages whose reference source changed after the translation
+//! was last updated, and pages whose heading structure differs.
+
+use crate::builder::{extract_headings, load_language_config, TranslatedPage, Translations};
+use crate::parser::{self, parse_front_matter, BookConfig};
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Coverage of every translation against the reference language
+#[derive(Debug, Serialize)]
+pub struct I18nStatusReport {
+    pub reference: String,
+    pub reference_pages: usize,
+    pub languages: Vec<LanguageStatus>,
+}
+
+/// Coverage of one translation
+#[derive(Debug, Serialize)]
+pub struct LanguageStatus {
+    pub code: String,
+    pub title: String,
+    /// Reference pages that have a translation
+    pub translated: usize,
+    /// Reference pages with no translation (source paths in the reference language)
+    pub missing: Vec<String>,
+    pub outdated: Vec<OutdatedPage>,
+    pub heading_mismatches: Vec<HeadingMismatch>,
+    /// Translated pages with no counterpart in the reference language
+    pub extra: Vec<String>,
+}
+
+/// A translation last updated before its reference page
+#[derive(Debug, Serialize)]
+pub struct OutdatedPage {
+    pub path: String,
+    pub reference_path: String,
+    /// Unix time of the last change: the last git commit when git tracks
+    /// both files, the file mtime otherwise (for both, so they compare)
+    pub reference_modified: u64,
+    pub translation_modified: u64,
+}
+
+/// A translation whose h2-h4 structure differs from its reference page
+#[derive(Debug, Serialize)]
+pub struct HeadingMismatch {
+    pub path: String,
+    pub reference_path: String,
+    pub reference_levels: Vec<u8>,
+    pub translation_levels: Vec<u8>,
+}
+
+/// Run the report and print it as a table (or JSON)
+pub fn i18n_status(source: &Path, reference: Option<&str>, json: bool) -> Result<()> {
+    let source = source.canonicalize().context("Source directory not found")?;
+    let report = build_report(&source, reference)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_table(&report);
+    }
+
+    Ok(())
+}
+
+fn build_report(source: &Path, reference: Option<&str>) -> Result<I18nStatusReport> {
+    let (config, config_file) = BookConfig::load_with_file(source)?;
+    let source = &config.content_dir(source)?;
+    let languages = parser::langs::parse_langs(source, &config.structure.langs)?;
+    if languages.is_empty() {
+        bail!("No LANGS.md found in {:?}. i18n-status needs a multi-language book", source);
+    }
+
+    let reference = match reference {
+        Some(code) => languages
+            .iter()
+            .find(|lang| lang.code == code)
+            .with_context(|| format!("Reference language '{}' is not listed in LANGS.md", code))?,
+        None => &languages[0],
+    };
+
+    let lang_configs = languages
+        .iter()
+        .map(|lang| load_language_config(source, lang, config_file.as_ref()).map(|(lang_config, _)| lang_config))
+        .collect::<Result<Vec<_>>>()?;
+    let translations = Translations::collect(source, &languages, &lang_configs)?;
+    let reference_dir = source.join(&reference.code);
+    let reference_pages: Vec<&TranslatedPage> = translations
+        .pages(&reference.code)
+        .iter()
+        .filter(|page| reference_dir.join(&page.md_path).exists())
+        .collect();
+
+    let mut statuses = Vec::new();
+    for lang in translations.languages() {
+        if lang.code == reference.code {
+            continue;
+        }
+        let lang_dir = source.join(&lang.code);
+        let mut status = LanguageStatus {
+            code: lang.code.clone(),
+            title: lang.title.clone(),
+            translated: 0,
+            missing: Vec::new(),
+            outdated: Vec::new(),
+            heading_mismatches: Vec::new(),
+            extra: Vec::new(),
+        };
+
+        for page in &reference_pages {
+            let translated = translations
+                .counterpart(&lang.code, &page.key)
+                .filter(|tp| lang_dir.join(&tp.md_path).exists());
+            let Some(translated) = translated else {
+                status.missing.push(page.md_path.clone());
+                continue;
+            };
+            status.translated += 1;
+
+            let reference_file = reference_dir.join(&page.md_path);
+            let translation_file = lang_dir.join(&translated.md_path);
+
+            if let Some((reference_modified, translation_modified)) = change_times(&reference_file, &translation_file) {
+                if reference_modified > translation_modified {
+                    status.outdated.push(OutdatedPage {
+                        path: translated.md_path.clone(),
+                        reference_path: page.md_path.clone(),
+                        reference_modified,
+                        translation_modified,
+                    });
+                }
+            }
+
+            let reference_levels = heading_levels(&reference_file);
+            let translation_levels = heading_levels(&translation_file);
+            if reference_levels != translation_levels {
+                status.heading_mismatches.push(HeadingMismatch {
+                    path: translated.md_path.clone(),
+                    reference_path: page.md_path.clone(),
+                    reference_levels,
+                    translation_levels,
+                });
+            }
+        }
+
+        for page in translations.pages(&lang.code) {
+            if translations.counterpart(&reference.code, &page.key).is_none() {
+                status.extra.push(page.md_path.clone());
+            }
+        }
+
+        statuses.push(status);
+    }
+
+    Ok(I18nStatusReport {
+        reference: reference.code.clone(),
+        reference_pages: reference_pages.len(),
+        languages: statuses,
+    })
+}
+
+/// Last change times of a reference page and its translation, from the same
+/// source: git commit times when git tracks both files, else their mtimes.
+/// (A checkout sets mtimes, so they can't be compared with commit times.)
+fn change_times(reference: &Path, translation: &Path) -> Option<(u64, u64)> {
+    if let (Some(reference_time), Some(translation_time)) = (git_commit_time(reference), git_commit_time(translation)) {
+        return Some((reference_time, translation_time));
+    }
+    Some((modified_time(reference)?, modified_time(translation)?))
+}
+
+fn modified_time(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+}
+
+fn git_commit_time(path: &Path) -> Option<u64> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(path.parent()?)
+        .args(["log", "-1", "--format=%ct", "--"])
+        .arg(path.file_name()?)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Heading levels (h2-h4, in document order) of a page
+fn heading_levels(path: &Path) -> Vec<u8> {
+    let Ok(raw_content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let parsed = parse_front_matter(&raw_content);
+    extract_headings(&parsed.content)
+        .iter()
+        .map(|item| item.level)
+        .collect()
+}
+
+fn print_table(report: &I18nStatusReport) {
+    println!("Reference language: {} ({} pages)", report.reference, report.reference_pages);
+
+    for status in &report.languages {
+        let percent = if report.reference_pages == 0 {
+            100.0
+        } else {
+            status.translated as f64 * 100.0 / report.reference_pages as f64
+        };
+        println!();
+        println!(
+            "{} ({}): {}/{} translated ({:.0}%)",
+            status.title, status.code, status.translated, report.reference_pages, percent
+        );
+
+        let mut rows: Vec<(&str, String, String)> = Vec::new();
+        for path in &status.missing {
+            rows.push(("missing", path.clone(), String::new()));
+        }
+        for page in &status.outdated {
+            let days = (page.reference_modified - page.translation_modified) / SECONDS_PER_DAY;
+            rows.push((
+                "outdated",
+                page.path.clone(),
+                format!("{} changed {} day(s) after translation", page.reference_path, days),
+            ));
+        }
+        for page in &status.heading_mismatches {
+            rows.push((
+                "headings",
+                page.path.clone(),
+                format!(
+                    "{} heading(s) in {}, {} in translation",
+                    page.reference_levels.len(),
+                    page.reference_path,
+                    page.translation_levels.len()
+                ),
+            ));
+        }
+        for path in &status.extra {
+            rows.push(("extra", path.clone(), "not in reference".to_string()));
+        }
+
+        if rows.is_empty() {
+            println!("  Up to date");
+            continue;
+        }
+
+        let width = rows.iter().map(|(_, path, _)| path.chars().count()).max().unwrap_or(0).max(4);
+        println!("  {:<9} {:<width$}  DETAIL", "STATUS", "PAGE", width = width);
+        for (kind, path, detail) in rows {
+            println!("  {:<9} {:<width$}  {}", kind, path, detail, width = width);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    fn write_book(name: &str, files: &[(&str, &str)]) -> TempDir {
+        let dir = TempDir::new(&format!("i18n-status-{}", name));
+        dir.write_files(files);
+        dir
+    }
+
+    #[test]
+    fn test_missing_extra_and_headings() {
+        let dir = write_book("basic", &[
+            ("LANGS.md", "* [English](en/)\n* [日本語](ja/)\n"),
+            ("en/SUMMARY.md", "* [A](a.md)\n* [B](b.md)\n* [C](c.md)\n"),
+            ("en/a.md", "# A\n## One\n## Two\n"),
+            ("en/b.md", "# B\n"),
+            ("en/c.md", "---\ntranslation_key: cee\n---\n# C\n"),
+            ("ja/SUMMARY.md", "* [A](a.md)\n* [C](shi.md)\n* [Old](old.md)\n"),
+            ("ja/a.md", "# A\n## One\n"),
+            ("ja/shi.md", "---\ntranslation_key: cee\n---\n# C\n"),
+            ("ja/old.md", "# Old\n"),
+        ]);
+
+        let report = build_report(dir.path(), None).unwrap();
+        assert_eq!(report.reference, "en");
+        assert_eq!(report.reference_pages, 3);
+
+        let ja = &report.languages[0];
+        assert_eq!(ja.translated, 2);
+        assert_eq!(ja.missing, vec!["b.md"]);
+        assert_eq!(ja.extra, vec!["old.md"]);
+        assert_eq!(ja.heading_mismatches.len(), 1);
+        assert_eq!(ja.heading_mismatches[0].reference_levels, vec![2, 2]);
+        assert_eq!(ja.heading_mismatches[0].translation_levels, vec![2]);
+    }
+
+    #[test]
+    fn test_unknown_reference_language() {
+        let dir = write_book("unknown-ref", &[
+            ("LANGS.md", "* [English](en/)\n"),
+            ("en/SUMMARY.md", "* [A](a.md)\n"),
+        ]);
+        assert!(build_report(dir.path(), Some("fr")).is_err());
+    }
+}
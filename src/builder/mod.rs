@@ -1,24 +1,31 @@
+mod glossary_page;
+mod i18n;
+mod i18n_status;
+mod plugins;
 mod renderer;
+mod search;
 mod template;
+mod themes;
+mod translations;
 
-use crate::parser::{self, apply_glossary, parse_front_matter, BookConfig, Glossary, Language, Summary, SummaryItem};
+use crate::parser::config_file::ConfigFile;
+use crate::parser::{self, compat, parse_front_matter, BookConfig, Glossary, Language, Summary, SummaryItem};
 use anyhow::{Context, Result};
 use regex::Regex;
-use serde::Serialize;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 use std::time::Instant;
 
-pub use renderer::{render_markdown, render_markdown_with_path, render_markdown_with_hardbreaks, extract_headings, TocItem};
-pub use template::Templates;
-
-/// Search index entry
-#[derive(Serialize)]
-struct SearchEntry {
-    title: String,
-    path: String,
-    content: String,
-}
+pub use renderer::{render_markdown_with_path, render_markdown_with_hardbreaks, extract_headings, TocItem};
+pub use i18n::UiStrings;
+pub use i18n_status::i18n_status;
+pub use template::{eject_theme, Templates};
+pub use translations::{LanguageLink, LanguageView, TranslatedPage, Translations};
+use glossary_page::GlossaryPage;
+use plugins::{PageInfo, Plugins};
+use search::{SearchLibrary, SearchPages};
+use themes::Themes;
 
 /// Build statistics
 #[derive(Default)]
@@ -33,6 +40,11 @@ const GITBOOK_JS: &str = include_str!("../../templates/gitbook.js");
 const COLLAPSIBLE_JS: &str = include_str!("../../templates/collapsible.js");
 const FONTSETTINGS_JS: &str = include_str!("../../templates/fontsettings.js");
 const SEARCH_JS: &str = include_str!("../../templates/search.js");
+const THEME_JS: &str = include_str!("../../templates/theme.js");
+const LANG_REDIRECT_JS: &str = include_str!("../../templates/lang-redirect.js");
+
+/// `href` and `src` attributes of rendered HTML: (space and attribute name, URL)
+static LINK_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(\s(?:href|src))="([^"]*)""#).unwrap());
 
 /// Build the book from source directory to output directory
 pub fn build(source: &Path, output: &Path) -> Result<()> {
@@ -45,24 +57,37 @@ pub fn build_with_options(source: &Path, output: &Path, skip_search_index: bool)
     let source = source.canonicalize().context("Source directory not found")?;
 
     println!("Loading book configuration...");
-    let config = BookConfig::load(&source)?;
+    let (config, config_file) = BookConfig::load_with_file(&source)?;
     println!("  Title: {}", if config.title.is_empty() { "(untitled)" } else { &config.title });
+    // Book files live under `root` when book.json sets it
+    let source = config.content_dir(&source)?;
+    report_compatibility(config_file.as_ref(), &[&source], &config)?;
 
     // Check for multi-language book
-    let languages = parser::langs::parse_langs(&source)?;
+    let languages = parser::langs::parse_langs(&source, &config.structure.langs)?;
+
+    // Combined search over all languages and other books (book.json `search`)
+    let library = SearchLibrary::load(&source, &config)?;
 
     let stats = if languages.is_empty() {
         // Single language book
         println!("Building single-language book...");
-        build_single_book(&source, output, &config, skip_search_index)?
+        let search_library = library.as_ref().map(|_| "search_library.json");
+        let stats = build_single_book(&source, output, &config, skip_search_index, None, search_library)?;
+        if let Some(mut library) = library.filter(|_| !skip_search_index) {
+            library.add_own(&config.title, config.html_lang(), "", "");
+            library.write(output)?;
+        }
+        stats
     } else {
         // Multi-language book
         println!("Building multi-language book with {} languages:", languages.len());
         for lang in &languages {
-            println!("  - {} ({})", lang.title, lang.code);
+            let hidden = if lang.hidden { ", hidden" } else { "" };
+            println!("  - {} ({}{})", lang.title, lang.code, hidden);
         }
 
-        build_multi_lang_book(&source, output, &config, &languages, skip_search_index)?
+        build_multi_lang_book(&source, output, &config, config_file.as_ref(), &languages, skip_search_index, library)?
     };
 
     let elapsed = start_time.elapsed();
@@ -75,22 +100,35 @@ pub fn build_with_options(source: &Path, output: &Path, skip_search_index: bool)
     Ok(())
 }
 
-fn build_single_book(source: &Path, output: &Path, config: &BookConfig, skip_search_index: bool) -> Result<BuildStats> {
-    let summary = Summary::parse(source)?;
-    let templates = Templates::new(config)?;
+fn build_single_book(
+    source: &Path,
+    output: &Path,
+    config: &BookConfig,
+    skip_search_index: bool,
+    language: Option<LanguageView>,
+    search_library: Option<&str>,
+) -> Result<BuildStats> {
+    let summary = Summary::parse(source, &config.structure.summary)?;
+    // Theme templates and plugins of a language override those of the book root
+    let mut book_dirs = vec![source];
+    book_dirs.extend(language.map(|view| view.book_root()));
+    let templates = Templates::new(&book_dirs, config)?;
+    let mut plugins = Plugins::load(&book_dirs, config)?;
     let mut stats = BuildStats::default();
 
     // Load glossary if exists
-    let glossary = Glossary::load(source)?;
+    let glossary = Glossary::load(source, &config.structure.glossary)?;
     if !glossary.is_empty() {
-        println!("  Loaded glossary with {} terms", glossary.entries.len());
+        println!("  Loaded glossary with {} terms", glossary.terms.len());
     }
+    let mut glossary_page = GlossaryPage::new(&glossary, config);
 
     // Create output directory
     fs::create_dir_all(output)?;
 
     // Write embedded static assets
     write_static_assets(output, config)?;
+    plugins.write_assets(output)?;
 
     // Copy assets
     stats.assets += copy_assets(source, output)?;
@@ -105,26 +143,42 @@ fn build_single_book(source: &Path, output: &Path, config: &BookConfig, skip_sea
         }
     }
 
-    // Build each chapter
-    stats.pages += build_chapters(source, output, &summary.items, config, &templates, &summary, &glossary)?;
+    // Build each chapter, collecting the processed pages for the search index
+    let mut search_pages = SearchPages::default();
+    stats.pages += build_chapters(source, output, &summary.items, config, &templates, &summary, &mut glossary_page, language, &mut search_pages, &mut plugins)?;
 
-    // Generate index.html from README.md if exists
-    let readme_path = source.join("README.md");
-    if readme_path.exists() {
+    // Generate index.html from README.md (`structure.readme`) if exists
+    let readme = config.structure.readme.as_str();
+    if let Some((readme_path, fallback_lang)) = resolve_page_source(source, readme, language) {
         let raw_content = fs::read_to_string(&readme_path)?;
         // Parse front matter
         let parsed = parse_front_matter(&raw_content);
         let front_matter = parsed.front_matter;
+        // Use front matter title if available, otherwise use config title
+        let page_title = front_matter.as_ref()
+            .and_then(|fm| fm.title.as_deref())
+            .unwrap_or(&config.title);
+        let page = PageInfo { path: readme, output: "index.html", title: page_title, front_matter: front_matter.as_ref() };
+        let content = plugins.pre_markdown(&page, parsed.content)?;
         // Expand variables before rendering
-        let content = expand_variables(&parsed.content, config);
+        let content = expand_variables(&content, config);
         let html_content = render_markdown_with_hardbreaks(&content, config.hardbreaks);
+        let html_content = localize_callouts(&html_content, config);
         // Apply glossary terms
-        let html_content = apply_glossary(&html_content, &glossary);
+        let (html_content, glossary_terms) = glossary_page.apply(&html_content, &page, config);
+        let html_content = plugins.post_html(&page, html_content)?;
         let toc_items = extract_headings(&content);
-        // Use front matter title if available, otherwise use config title
-        let page_title = front_matter.as_ref()
+        let search_title = front_matter.as_ref()
             .and_then(|fm| fm.title.as_deref())
-            .unwrap_or(&config.title);
+            .unwrap_or("Home");
+        search_pages.insert("index.html", search_title, &html_content, &toc_items, front_matter.as_ref());
+        let html_content = match fallback_lang {
+            Some(lang) => untranslated_page(&html_content, "index.html", lang, config),
+            None => html_content,
+        };
+        let html_content = html_content + &glossary_page.popovers(&glossary_terms, "index.html");
+        let language_links = language_links_for(language, "index.html");
+        let plugin_page = plugins.page(&page)?;
         let page_html = templates.render_page_with_meta(
             page_title,
             &html_content,
@@ -134,19 +188,190 @@ fn build_single_book(source: &Path, output: &Path, config: &BookConfig, skip_sea
             Some("index.html"),
             &toc_items,
             front_matter.as_ref(),
+            &language_links,
+            &plugin_page,
         )?;
         fs::write(output.join("index.html"), page_html)?;
         stats.pages += 1;
     }
 
+    // Generate the glossary page, with the pages each term was found on
+    if !glossary.is_empty() {
+        stats.pages += write_glossary_page(output, &glossary_page, config, &templates, &summary, language, &mut search_pages, &mut plugins)?;
+    }
+
     // Generate search index (skip on hot reload for performance)
     if !skip_search_index {
-        generate_search_index(source, output, &summary)?;
+        search::generate_search_index(output, &summary, config, &search_pages, &glossary, search_library)?;
     }
 
+    plugins.finish(output)?;
+
     Ok(stats)
 }
 
+/// Render GLOSSARY.md (`structure.glossary`) as a page listing every term
+#[allow(clippy::too_many_arguments)]
+fn write_glossary_page(
+    output: &Path,
+    glossary_page: &GlossaryPage,
+    config: &BookConfig,
+    templates: &Templates,
+    summary: &Summary,
+    language: Option<LanguageView>,
+    search_pages: &mut SearchPages,
+    plugins: &mut Plugins,
+) -> Result<usize> {
+    let html_path = glossary_page.html_path.as_str();
+    let title = glossary_page.title(&UiStrings::for_config(config));
+    let page = PageInfo { path: &config.structure.glossary, output: html_path, title: &title, front_matter: None };
+    let (html_content, toc_items) = glossary_page.render(config);
+    let html_content = plugins.post_html(&page, html_content)?;
+    search_pages.insert(html_path, &title, &html_content, &toc_items, None);
+
+    let depth = html_path.matches('/').count();
+    let root_path = if depth > 0 { "../".repeat(depth) } else { "./".to_string() };
+    let language_links = language_links_for(language, html_path);
+    let plugin_page = plugins.page(&page)?;
+    let page_html = templates.render_page_with_meta(
+        &title,
+        &html_content,
+        &root_path,
+        config,
+        summary,
+        Some(html_path),
+        &toc_items,
+        None,
+        &language_links,
+        &plugin_page,
+    )?;
+
+    let dest_file = output.join(html_path);
+    if let Some(parent) = dest_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(dest_file, page_html)?;
+    Ok(1)
+}
+
+/// Print the config files that are ignored, and the keys and plugins of the
+/// config that guidebook doesn't understand
+fn report_compatibility(config_file: Option<&ConfigFile>, book_dirs: &[&Path], config: &BookConfig) -> Result<()> {
+    let Some(config_file) = config_file else {
+        return Ok(());
+    };
+    for ignored in &config_file.ignored {
+        println!("  Note: {} is ignored, {} takes precedence", ignored.display(), config_file.file_name());
+    }
+    let findings = compat::check_config(
+        &config_file.value,
+        BookConfig::is_builtin_plugin,
+        |name| plugins::is_available(name, config, book_dirs),
+    );
+    if !findings.is_empty() {
+        println!("  Compatibility report for {}:", config_file.path.display());
+        for finding in &findings {
+            println!("    - {}", finding);
+        }
+    }
+    Ok(())
+}
+
+/// Check the config files of a book and its languages (`guidebook config validate`).
+/// Errors stop a build; warnings are what the compatibility report lists.
+pub fn validate_config(source: &Path) -> Result<()> {
+    let source = source.canonicalize().context("Source directory not found")?;
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    let config = match ConfigFile::find(&source) {
+        Ok(Some(file)) => validate_config_file(&file, &source, &mut errors, &mut warnings),
+        Ok(None) => {
+            println!("No config file in {}; the defaults are used", source.display());
+            Some(BookConfig::default())
+        }
+        Err(e) => {
+            println!("  error: {}", e);
+            errors += 1;
+            None
+        }
+    };
+
+    // Language configs of a multi-language book
+    if let Some(config) = &config {
+        let content_dir = config.content_dir(&source).unwrap_or_else(|_| source.clone());
+        for lang in parser::langs::parse_langs(&content_dir, &config.structure.langs)? {
+            let lang_source = content_dir.join(&lang.code);
+            match ConfigFile::find(&lang_source) {
+                Ok(Some(file)) => {
+                    validate_config_file(&file, &content_dir, &mut errors, &mut warnings);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    println!("  error: {}", e);
+                    errors += 1;
+                }
+            }
+        }
+    }
+
+    println!();
+    if errors == 0 && warnings == 0 {
+        println!("No problems found");
+        return Ok(());
+    }
+    println!("{} error(s), {} warning(s)", errors, warnings);
+    if errors > 0 {
+        anyhow::bail!("the configuration has {} error(s)", errors);
+    }
+    Ok(())
+}
+
+/// Print the problems of one config file. Returns the config when it can be loaded.
+fn validate_config_file(file: &ConfigFile, book_root: &Path, errors: &mut usize, warnings: &mut usize) -> Option<BookConfig> {
+    println!("Checking {}", file.path.display());
+    let book_dir = file.path.parent().unwrap_or(book_root);
+
+    let mut schema_errors = parser::schema::validate(&file.value);
+    schema_errors.sort_by_key(|error| file.locate(&error.key));
+    for error in &schema_errors {
+        println!("  error: {}: {}", file.location(&error.key), error);
+    }
+    *errors += schema_errors.len();
+
+    let config = if schema_errors.is_empty() {
+        match serde_json::from_value::<BookConfig>(file.value.clone()) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                println!("  error: {}: {}", file.path.display(), e);
+                *errors += 1;
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    if let Some(Err(e)) = config.as_ref().map(|config| config.content_dir(book_dir)) {
+        println!("  error: {}: {}", file.location(&["root".to_string()]), e);
+        *errors += 1;
+    }
+
+    let plugin_config = config.clone().unwrap_or_default();
+    let content_dir = plugin_config.content_dir(book_dir).unwrap_or_else(|_| book_dir.to_path_buf());
+    let findings = compat::check_config(
+        &file.value,
+        BookConfig::is_builtin_plugin,
+        |name| plugins::is_available(name, &plugin_config, &[&content_dir, book_root]),
+    );
+    for finding in &findings {
+        println!("  warning: {}: {}", file.location(&finding.key), finding);
+    }
+    *warnings += findings.len();
+
+    config
+}
+
 fn write_static_assets(output: &Path, config: &BookConfig) -> Result<()> {
     let gitbook_dir = output.join("gitbook");
     fs::create_dir_all(&gitbook_dir)?;
@@ -156,6 +381,13 @@ fn write_static_assets(output: &Path, config: &BookConfig) -> Result<()> {
 
     // Write JS
     fs::write(gitbook_dir.join("gitbook.js"), GITBOOK_JS)?;
+    fs::write(gitbook_dir.join("theme.js"), THEME_JS)?;
+
+    // Write colors of custom themes
+    let themes = Themes::from_config(config)?;
+    if themes.has_custom() {
+        fs::write(gitbook_dir.join("themes.css"), themes.custom_css())?;
+    }
 
     // Write collapsible JS only if plugin is enabled
     if config.is_plugin_enabled("collapsible-chapters") {
@@ -177,36 +409,69 @@ fn build_multi_lang_book(
     source: &Path,
     output: &Path,
     config: &BookConfig,
+    config_file: Option<&ConfigFile>,
     languages: &[Language],
     skip_search_index: bool,
+    mut library: Option<SearchLibrary>,
 ) -> Result<BuildStats> {
     let mut stats = BuildStats::default();
 
     // Create output directory
     fs::create_dir_all(output)?;
 
-    // Generate language index page
-    generate_lang_index(output, languages, config)?;
+    // Generate language index page; its search box needs search_library.json,
+    // which is not written when the search index is skipped
+    generate_lang_index(output, languages, config, library.is_some() && !skip_search_index)?;
+
+    // Language configs are loaded up front: their `structure` names the
+    // files matched across languages
+    let (lang_configs, lang_files): (Vec<_>, Vec<_>) = languages
+        .iter()
+        .map(|lang| load_language_config(source, lang, config_file))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+
+    // Match pages across languages for the per-page language switcher
+    let translations = Translations::collect(source, languages, &lang_configs)?;
 
     // Build each language
-    for lang in languages {
+    for ((lang, lang_config), lang_file) in languages.iter().zip(lang_configs).zip(lang_files) {
         println!("\nBuilding {} ({})...", lang.title, lang.code);
         let lang_source = source.join(&lang.code);
         let lang_output = output.join(&lang.code);
 
-        // Use language-specific config if exists, otherwise use root config
-        let lang_config_path = lang_source.join("book.json");
-        let lang_config = if lang_config_path.exists() {
-            BookConfig::load(&lang_source)?
-        } else {
-            config.clone()
-        };
+        report_compatibility(lang_file.as_ref(), &[&lang_source, source], &lang_config)?;
+        let mut lang_config = lang_config;
+        // Fall back to the LANGS.md tag for <html lang>; a `dir=` there
+        // overrides the direction inherited from the root config
+        if lang_config.language.is_none() {
+            lang_config.language = Some(lang.lang.clone());
+        }
+        if lang.direction.is_some() {
+            lang_config.direction = lang.direction.clone();
+        }
 
-        let lang_stats = build_single_book(&lang_source, &lang_output, &lang_config, skip_search_index)?;
+        let lang_stats = build_single_book(
+            &lang_source,
+            &lang_output,
+            &lang_config,
+            skip_search_index,
+            Some(translations.for_language(&lang.code).with_fallback(lang_config.fallback.as_deref())),
+            library.as_ref().map(|_| "../search_library.json"),
+        )?;
+        if let Some(library) = library.as_mut() {
+            let book = if lang_config.title.is_empty() { &config.title } else { &lang_config.title };
+            library.add_own(book, &lang.lang, &lang.title, &lang.code);
+        }
         stats.pages += lang_stats.pages;
         stats.assets += lang_stats.assets;
     }
 
+    if let Some(library) = library.filter(|_| !skip_search_index) {
+        library.write(output)?;
+    }
+
     // Copy root assets if they exist
     let assets_dir = source.join("assets");
     if assets_dir.exists() {
@@ -216,6 +481,12 @@ fn build_multi_lang_book(
     Ok(stats)
 }
 
+/// Config of a language: its own config file merged over the root config
+fn load_language_config(source: &Path, lang: &Language, root_file: Option<&ConfigFile>) -> Result<(BookConfig, Option<ConfigFile>)> {
+    BookConfig::load_language(&source.join(&lang.code), root_file)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn build_chapters(
     source: &Path,
     output: &Path,
@@ -223,12 +494,20 @@ fn build_chapters(
     config: &BookConfig,
     templates: &Templates,
     summary: &Summary,
-    glossary: &Glossary,
+    glossary: &mut GlossaryPage,
+    language: Option<LanguageView>,
+    search_pages: &mut SearchPages,
+    plugins: &mut Plugins,
 ) -> Result<usize> {
     let mut built_files: std::collections::HashSet<String> = std::collections::HashSet::new();
-    build_chapters_inner(source, output, items, config, templates, summary, glossary, &mut built_files)
+    // A SUMMARY.md link to the glossary is the page write_glossary_page generates
+    if !glossary.glossary.is_empty() {
+        built_files.insert(config.structure.glossary.clone());
+    }
+    build_chapters_inner(source, output, items, config, templates, summary, glossary, language, search_pages, plugins, &mut built_files)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn build_chapters_inner(
     source: &Path,
     output: &Path,
@@ -236,7 +515,10 @@ fn build_chapters_inner(
     config: &BookConfig,
     templates: &Templates,
     summary: &Summary,
-    glossary: &Glossary,
+    glossary: &mut GlossaryPage,
+    language: Option<LanguageView>,
+    search_pages: &mut SearchPages,
+    plugins: &mut Plugins,
     built_files: &mut std::collections::HashSet<String>,
 ) -> Result<usize> {
     let mut count = 0;
@@ -255,13 +537,16 @@ fn build_chapters_inner(
                 if base_path.is_empty() || built_files.contains(base_path) {
                     // Still need to process children
                     if !children.is_empty() {
-                        count += build_chapters_inner(source, output, children, config, templates, summary, glossary, built_files)?;
+                        count += build_chapters_inner(source, output, children, config, templates, summary, glossary, language, search_pages, plugins, built_files)?;
                     }
                     continue;
                 }
 
-                let src_file = source.join(base_path);
-                if src_file.exists() {
+                if let Some((src_file, fallback_lang)) = resolve_page_source(source, base_path, language) {
+                    if let Some(lang) = fallback_lang {
+                        println!("  Note: {} not translated, using {} version", base_path, lang.code);
+                    }
+
                     // Mark as built before processing
                     built_files.insert(base_path.to_string());
 
@@ -270,16 +555,34 @@ fn build_chapters_inner(
                     // Parse front matter
                     let parsed = parse_front_matter(&raw_content);
                     let front_matter = parsed.front_matter;
+
+                    // Generate output path (use base_path without anchor)
+                    let html_path = base_path.replace(".md", ".html");
+                    let dest_file = output.join(&html_path);
+
+                    // Use front matter title if available, otherwise use summary title
+                    let page_title = front_matter.as_ref()
+                        .and_then(|fm| fm.title.as_deref())
+                        .unwrap_or(title);
+                    let page = PageInfo { path: base_path, output: &html_path, title: page_title, front_matter: front_matter.as_ref() };
+
+                    let content = plugins.pre_markdown(&page, parsed.content)?;
                     // Expand variables before rendering
-                    let content = expand_variables(&parsed.content, config);
+                    let content = expand_variables(&content, config);
                     let html_content = render_markdown_with_path(&content, Some(base_path), config.hardbreaks);
+                    let html_content = localize_callouts(&html_content, config);
                     // Apply glossary terms
-                    let html_content = apply_glossary(&html_content, glossary);
+                    let (html_content, glossary_terms) = glossary.apply(&html_content, &page, config);
+                    let html_content = plugins.post_html(&page, html_content)?;
                     let toc_items = extract_headings(&content);
 
-                    // Generate output path (use base_path without anchor)
-                    let html_path = base_path.replace(".md", ".html");
-                    let dest_file = output.join(&html_path);
+                    search_pages.insert(&html_path, page_title, &html_content, &toc_items, front_matter.as_ref());
+                    let html_content = match fallback_lang {
+                        Some(lang) => untranslated_page(&html_content, &html_path, lang, config),
+                        None => html_content,
+                    };
+                    // Definitions of the terms, kept out of the search index
+                    let html_content = html_content + &glossary.popovers(&glossary_terms, &html_path);
 
                     // Calculate relative path to root
                     let depth = html_path.matches('/').count();
@@ -289,10 +592,8 @@ fn build_chapters_inner(
                         "./".to_string()
                     };
 
-                    // Use front matter title if available, otherwise use summary title
-                    let page_title = front_matter.as_ref()
-                        .and_then(|fm| fm.title.as_deref())
-                        .unwrap_or(title);
+                    let language_links = language_links_for(language, &html_path);
+                    let plugin_page = plugins.page(&page)?;
 
                     // Render with template
                     let page_html = templates.render_page_with_meta(
@@ -304,6 +605,8 @@ fn build_chapters_inner(
                         Some(&html_path),
                         &toc_items,
                         front_matter.as_ref(),
+                        &language_links,
+                        &plugin_page,
                     )?;
 
                     // Write output
@@ -319,7 +622,7 @@ fn build_chapters_inner(
 
             // Build children recursively
             if !children.is_empty() {
-                count += build_chapters_inner(source, output, children, config, templates, summary, glossary, built_files)?;
+                count += build_chapters_inner(source, output, children, config, templates, summary, glossary, language, search_pages, plugins, built_files)?;
             }
         }
     }
@@ -327,6 +630,85 @@ fn build_chapters_inner(
     Ok(count)
 }
 
+/// Locate a page's source file. When this language has no such file, use the
+/// fallback language's file and return that language too
+fn resolve_page_source<'a>(
+    source: &Path,
+    md_path: &str,
+    language: Option<LanguageView<'a>>,
+) -> Option<(PathBuf, Option<&'a Language>)> {
+    let src_file = source.join(md_path);
+    if src_file.exists() {
+        return Some((src_file, None));
+    }
+    let (fallback_file, lang) = language?.fallback_file(md_path)?;
+    Some((fallback_file, Some(lang)))
+}
+
+/// Wrap a page rendered from the fallback language with a "not yet translated" notice
+fn untranslated_page(html_content: &str, html_path: &str, fallback: &Language, config: &BookConfig) -> String {
+    let strings = UiStrings::for_config(config);
+    let notice = strings
+        .get("untranslated_notice")
+        .replace("{language}", &fallback.title);
+    format!(
+        "<div class=\"untranslated-notice\" role=\"note\">{}</div>\n<div lang=\"{}\">\n{}</div>\n",
+        renderer::html_escape(&notice),
+        renderer::html_escape(&fallback.lang),
+        link_fallback_assets(html_content, html_path, &fallback.code)
+    )
+}
+
+/// Point the relative asset links (images, downloads) of a page rendered
+/// from the fallback language at that language's output, where its assets
+/// were copied. Links to pages stay in the current language.
+fn link_fallback_assets(html_content: &str, html_path: &str, fallback_code: &str) -> String {
+    // From the page to the same directory in the fallback language's output
+    let dir = html_path.rfind('/').map_or("", |slash| &html_path[..=slash]);
+    let base = format!("{}../{}/{}", "../".repeat(html_path.matches('/').count()), fallback_code, dir);
+
+    LINK_ATTRIBUTE.replace_all(html_content, |caps: &regex::Captures| {
+        let url = &caps[2];
+        let path = url.split(['#', '?']).next().unwrap_or("");
+        let relative = !path.is_empty()
+            && !url.starts_with('/')
+            && !url.split('/').next().is_some_and(|first| first.contains(':'));
+        if relative && !path.ends_with(".html") {
+            format!("{}=\"{}{}\"", &caps[1], base, url)
+        } else {
+            caps[0].to_string()
+        }
+    })
+    .to_string()
+}
+
+/// Translate the default titles of callouts ("Note", "Warning") into the book language
+fn localize_callouts(html_content: &str, config: &BookConfig) -> String {
+    if !html_content.contains("<p class=\"callout-title\">") {
+        return html_content.to_string();
+    }
+    static TITLE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"(<div class="callout callout-(\w+)" role="note">\n<p class="callout-title">)([^<]*)</p>"#).unwrap()
+    });
+    let strings = UiStrings::for_config(config);
+    TITLE.replace_all(html_content, |caps: &regex::Captures| {
+        let kind = &caps[2];
+        if renderer::callout_title(kind) != Some(&caps[3]) {
+            return caps[0].to_string();
+        }
+        let key = format!("callout_{}", kind);
+        format!("{}{}</p>", &caps[1], renderer::html_escape(strings.get(&key)))
+    })
+    .to_string()
+}
+
+/// Language switcher links for a page (empty for single-language books)
+fn language_links_for(language: Option<LanguageView>, html_path: &str) -> Vec<LanguageLink> {
+    language
+        .map(|view| view.links_for(html_path))
+        .unwrap_or_default()
+}
+
 fn copy_assets(source: &Path, output: &Path) -> Result<usize> {
     let mut count = 0;
     // Copy common asset directories
@@ -363,31 +745,100 @@ fn copy_dir_recursive_count(src: &Path, dest: &Path) -> Result<usize> {
     Ok(count)
 }
 
-fn generate_lang_index(output: &Path, languages: &[Language], config: &BookConfig) -> Result<()> {
+fn generate_lang_index(
+    output: &Path,
+    languages: &[Language],
+    config: &BookConfig,
+    global_search: bool,
+) -> Result<()> {
+    let strings = UiStrings::for_config(config);
+    let choose_language = renderer::html_escape(strings.get("choose_language"));
     let title = if config.title.is_empty() {
-        "Select Language"
+        choose_language.clone()
     } else {
-        &config.title
+        renderer::html_escape(&config.title)
     };
 
+    let listed: Vec<&Language> = languages.iter().filter(|lang| !lang.hidden).collect();
+    let default = listed.iter().find(|lang| lang.default).or(listed.first());
+
     let mut lang_links = String::new();
-    for lang in languages {
+    let mut alternates = String::new();
+    for lang in &listed {
+        let dir_attr = if lang.is_rtl() { r#" dir="rtl""# } else { "" };
+        let tag = renderer::html_escape(&lang.lang);
+        let code = renderer::html_escape(&lang.code);
         lang_links.push_str(&format!(
             r#"
             <li>
-                <a href="{}/">{}</a>
+                <a href="{}/" lang="{}" hreflang="{}"{}>{}</a>
             </li>
         "#,
-            lang.code, lang.title
+            code, tag, tag, dir_attr, renderer::html_escape(&lang.title)
+        ));
+        alternates.push_str(&format!(
+            r#"
+        <link rel="alternate" hreflang="{}" href="{}/">"#,
+            tag, code
+        ));
+    }
+    if let Some(lang) = default {
+        alternates.push_str(&format!(
+            r#"
+        <link rel="alternate" hreflang="x-default" href="{}/">"#,
+            renderer::html_escape(&lang.code)
         ));
     }
 
+    // Send readers to their browser language (book.json `languageRedirect`);
+    // the list stays for readers without JavaScript or coming back to it
+    let redirect_head = match default.filter(|_| config.language_redirect) {
+        Some(default) => {
+            let targets: Vec<serde_json::Value> = listed
+                .iter()
+                .map(|lang| serde_json::json!({ "code": lang.code, "lang": lang.lang }))
+                .collect();
+            format!(
+                r#"
+        <script src="gitbook/lang-redirect.js" data-languages="{}" data-default="{}"></script>"#,
+                renderer::html_escape(&serde_json::Value::from(targets).to_string()),
+                renderer::html_escape(&default.code)
+            )
+        }
+        None => String::new(),
+    };
+
+    let dir_attr = if config.is_rtl() { r#" dir="rtl""# } else { "" };
+
+    // Search box over every language (and library book) when global search is on
+    let (search_head, search_box) = if global_search {
+        (
+            r#"
+        <link rel="stylesheet" href="gitbook/gitbook.css">
+        <script src="gitbook/search.js" defer></script>"#
+                .to_string(),
+            format!(
+                r#"
+        <div class="search-wrapper">
+            <input type="text" class="search-input" placeholder="{}" aria-label="{}" data-no-results="{}" data-search-library="search_library.json">
+            <div class="search-results"></div>
+        </div>
+"#,
+                renderer::html_escape(strings.get("search_placeholder")),
+                renderer::html_escape(strings.get("search_label")),
+                renderer::html_escape(strings.get("search_no_results")),
+            ),
+        )
+    } else {
+        (String::new(), String::new())
+    };
+
     let html = format!(
         r#"<!DOCTYPE HTML>
-<html lang="" >
+<html lang="{}"{}>
     <head>
         <meta charset="UTF-8">
-        <title>Choose a language · {}</title>
+        <title>{} · {}</title>
         <meta http-equiv="X-UA-Compatible" content="IE=edge" />
         <meta name="description" content="">
         <meta name="generator" content="guidebook">
@@ -397,14 +848,14 @@ fn generate_lang_index(output: &Path, languages: &[Language], config: &BookConfi
         <meta name="apple-mobile-web-app-capable" content="yes">
         <meta name="apple-mobile-web-app-status-bar-style" content="black">
         <link rel="apple-touch-icon-precomposed" sizes="152x152" href="gitbook/images/apple-touch-icon-precomposed-152.png">
-        <link rel="shortcut icon" href="gitbook/images/favicon.ico" type="image/x-icon">
+        <link rel="shortcut icon" href="gitbook/images/favicon.ico" type="image/x-icon">{}{}{}
     </head>
     <body>
 
 <div class="book-langs-index" role="navigation">
     <div class="inner">
-        <h3>Choose a language</h3>
-
+        <h3>{}</h3>
+{}
         <ul class="languages">
         {}
         </ul>
@@ -413,94 +864,30 @@ fn generate_lang_index(output: &Path, languages: &[Language], config: &BookConfi
 
     </body>
 </html>"#,
-        title, lang_links
+        renderer::html_escape(config.html_lang()),
+        dir_attr,
+        choose_language,
+        title,
+        alternates,
+        redirect_head,
+        search_head,
+        choose_language,
+        search_box,
+        lang_links
     );
 
     fs::write(output.join("index.html"), html)?;
 
     // Copy gitbook static files to root for the language selector page
     copy_gitbook_static_to_root(output)?;
-
-    Ok(())
-}
-
-/// Strip HTML tags from content for search indexing
-fn strip_html_tags(html: &str) -> String {
-    let mut result = String::new();
-    let mut in_tag = false;
-
-    for c in html.chars() {
-        if c == '<' {
-            in_tag = true;
-        } else if c == '>' {
-            in_tag = false;
-        } else if !in_tag {
-            result.push(c);
-        }
+    if global_search {
+        let gitbook_dir = output.join("gitbook");
+        fs::write(gitbook_dir.join("gitbook.css"), GITBOOK_CSS)?;
+        fs::write(gitbook_dir.join("search.js"), SEARCH_JS)?;
     }
-
-    // Clean up whitespace
-    result
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
-}
-
-/// Collect search entries from summary items
-fn collect_search_entries(
-    source: &Path,
-    items: &[SummaryItem],
-    entries: &mut Vec<SearchEntry>,
-) -> Result<()> {
-    for item in items {
-        if let SummaryItem::Link { title, path, children } = item {
-            if let Some(md_path) = path {
-                let src_file = source.join(md_path);
-                if src_file.exists() {
-                    let content = fs::read_to_string(&src_file)?;
-                    let html_content = render_markdown(&content);
-                    let text_content = strip_html_tags(&html_content);
-                    let html_path = md_path.replace(".md", ".html");
-
-                    entries.push(SearchEntry {
-                        title: title.clone(),
-                        path: html_path,
-                        content: text_content,
-                    });
-                }
-            }
-            if !children.is_empty() {
-                collect_search_entries(source, children, entries)?;
-            }
-        }
+    if !redirect_head.is_empty() {
+        fs::write(output.join("gitbook").join("lang-redirect.js"), LANG_REDIRECT_JS)?;
     }
-    Ok(())
-}
-
-/// Generate search index JSON file
-fn generate_search_index(source: &Path, output: &Path, summary: &Summary) -> Result<()> {
-    let mut entries = Vec::new();
-
-    // Collect from README.md
-    let readme_path = source.join("README.md");
-    if readme_path.exists() {
-        let content = fs::read_to_string(&readme_path)?;
-        let html_content = render_markdown(&content);
-        let text_content = strip_html_tags(&html_content);
-
-        entries.push(SearchEntry {
-            title: "Home".to_string(),
-            path: "index.html".to_string(),
-            content: text_content,
-        });
-    }
-
-    // Collect from all chapters
-    collect_search_entries(source, &summary.items, &mut entries)?;
-
-    // Write search index
-    let json = serde_json::to_string(&entries)?;
-    fs::write(output.join("search_index.json"), json)?;
 
     Ok(())
 }
@@ -591,6 +978,7 @@ fn copy_gitbook_static_to_root(output: &Path) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::TempDir;
     use std::collections::HashMap;
 
     fn create_test_config(variables: HashMap<String, serde_json::Value>) -> BookConfig {
@@ -695,4 +1083,113 @@ mod tests {
 
         assert_eq!(result, "# Version 1.0.0\n\nThis is version 1.0.0.");
     }
+
+    #[test]
+    fn test_lang_index_escapes_strings() {
+        let dir = TempDir::new("lang-index");
+        let config: BookConfig = serde_json::from_str(
+            r#"{"title": "Q&A", "language": "en", "i18n": {"en": {"choose_language": "<Pick>"}}}"#,
+        )
+        .unwrap();
+        let languages = vec![Language::new("en", "English <EN>"), Language::new("ja", "日本語")];
+        generate_lang_index(dir.path(), &languages, &config, false).unwrap();
+
+        let html = fs::read_to_string(dir.path().join("index.html")).unwrap();
+        assert!(html.contains("<title>&lt;Pick&gt; · Q&amp;A</title>"), "{}", html);
+        assert!(html.contains("<h3>&lt;Pick&gt;</h3>"), "{}", html);
+        assert!(html.contains(">English &lt;EN&gt;</a>"), "{}", html);
+    }
+
+    #[test]
+    fn test_glossary_in_summary_built_once() {
+        let dir = TempDir::new("glossary-summary");
+        dir.write_files(&[
+            ("SUMMARY.md", "* [Setup](setup.md)\n* [Glossary](GLOSSARY.md)\n"),
+            ("README.md", "# Intro\n"),
+            ("setup.md", "# Setup\n\nInstall the CLI.\n"),
+            ("GLOSSARY.md", "## CLI\nCommand line interface\n"),
+        ]);
+        let output = dir.path().join("_book");
+        let stats = build_single_book(dir.path(), &output, &BookConfig::default(), false, None, None).unwrap();
+        // setup.html, index.html and GLOSSARY.html
+        assert_eq!(stats.pages, 3);
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output.join("search_index.json")).unwrap()).unwrap();
+        let glossary_docs = manifest["docs"].as_array().unwrap().iter().filter(|doc| doc[1] == "GLOSSARY.html" && doc[2] == "").count();
+        assert_eq!(glossary_docs, 1, "{}", manifest["docs"]);
+    }
+
+    #[test]
+    fn test_lang_index_search_without_index() {
+        let dir = TempDir::new("lang-index-search");
+        dir.write_files(&[
+            ("book.json", r#"{"search": {"global": true}}"#),
+            ("LANGS.md", "* [English](en/)\n* [日本語](ja/)\n"),
+            ("en/SUMMARY.md", "* [Intro](README.md)\n"),
+            ("en/README.md", "# Intro\n"),
+            ("ja/SUMMARY.md", "* [はじめに](README.md)\n"),
+            ("ja/README.md", "# はじめに\n"),
+        ]);
+        let output = dir.path().join("_book");
+
+        build_with_options(dir.path(), &output, true).unwrap();
+        let html = fs::read_to_string(output.join("index.html")).unwrap();
+        assert!(!html.contains("data-search-library"), "{}", html);
+        assert!(!output.join("search_library.json").exists());
+
+        build(dir.path(), &output).unwrap();
+        let html = fs::read_to_string(output.join("index.html")).unwrap();
+        assert!(html.contains(r#"data-search-library="search_library.json""#), "{}", html);
+        assert!(output.join("search_library.json").exists());
+    }
+
+    #[test]
+    fn test_untranslated_page_from_fallback() {
+        let dir = TempDir::new("fallback-build");
+        dir.write_files(&[
+            ("LANGS.md", "* [English](en/)\n* [日本語](ja/)\n"),
+            ("en/SUMMARY.md", "* [Intro](README.md)\n* [Setup](guide/setup.md)\n"),
+            ("en/README.md", "# Intro\n"),
+            ("en/guide/setup.md", "# Setup\n\n![Screen](../images/screen.png)\n\nSee [the intro](../README.md) and [the log](setup.log).\n"),
+            ("en/images/screen.png", "png"),
+            ("ja/book.json", r#"{"fallback": "en"}"#),
+            ("ja/SUMMARY.md", "* [はじめに](README.md)\n* [セットアップ](guide/setup.md)\n"),
+            ("ja/README.md", "# はじめに\n"),
+        ]);
+        let output = dir.path().join("_book");
+        build(dir.path(), &output).unwrap();
+
+        let html = fs::read_to_string(output.join("ja/guide/setup.html")).unwrap();
+        assert!(html.contains("<div class=\"untranslated-notice\" role=\"note\">"), "{}", html);
+        assert!(html.contains("<div lang=\"en\">"), "{}", html);
+        // Assets come from the English output, pages stay in Japanese
+        assert!(html.contains(r#"src="../../en/guide/../images/screen.png""#), "{}", html);
+        assert!(html.contains(r#"href="../../en/guide/../setup.log""#), "{}", html);
+        assert!(html.contains(r#"href="../README.html""#), "{}", html);
+        assert!(output.join("ja/guide/../../en/guide/../images/screen.png").exists());
+
+        // The English page's switcher shows the Japanese page as untranslated
+        let html = fs::read_to_string(output.join("en/guide/setup.html")).unwrap();
+        assert!(html.contains(r#"<li class="untranslated">"#), "{}", html);
+    }
+
+    #[test]
+    fn test_link_fallback_assets() {
+        let html = r#"<img data-src="lazy.png" src="screen.png"><a href="https://example.com" data-href="x.zip">"#;
+        assert_eq!(
+            link_fallback_assets(html, "guide/setup.html", "en"),
+            r#"<img data-src="lazy.png" src="../../en/guide/screen.png"><a href="https://example.com" data-href="x.zip">"#
+        );
+    }
+
+    #[test]
+    fn test_localize_callouts() {
+        let config: BookConfig = serde_json::from_str(r#"{"language": "ja"}"#).unwrap();
+        let html = render_markdown_with_hardbreaks("> [!WARNING]\n> Careful\n\n::: tip Custom\nText\n:::\n", false);
+        let html = localize_callouts(&html, &config);
+        assert!(html.contains("<p class=\"callout-title\">警告</p>"), "HTML: {}", html);
+        // Titles written in the page are kept
+        assert!(html.contains("<p class=\"callout-title\">Custom</p>"), "HTML: {}", html);
+    }
 }
@@ -0,0 +1,507 @@
+//! Plugins beyond the built-in ones
+//!
+//! Any entry of `plugins` that is not built in is an external plugin: an
+//! executable started once per build that exchanges one JSON object per line
+//! over stdin/stdout. It is found, in order, at `pluginsConfig.<name>.command`,
+//! `plugins/<name>` in the book, or `guidebook-plugin-<name>` on the PATH:
+//! ```json
+//! {
+//!     "plugins": ["word-count"],
+//!     "pluginsConfig": {
+//!         "word-count": { "command": "./tools/word-count.py", "wordsPerMinute": 200 }
+//!     }
+//! }
+//! ```
+//!
+//! guidebook first sends `{"hook": "init", ...}` and the plugin answers with
+//! the hooks it implements (`{"hooks": ["pre-markdown", "post-html"]}`).
+//! Only those hooks are sent afterwards:
+//!
+//! | Hook | Request | Response |
+//! |------|---------|----------|
+//! | `pre-markdown` | `page`, `content` (Markdown) | `content` |
+//! | `post-html` | `page`, `content` (HTML) | `content` |
+//! | `page-context` | `page` | `context`: template variables, `{{ plugins.<name>.<key> }}` |
+//! | `assets` | | `assets`: `[{"name": "x.css", "content": "..."}]` |
+//! | `finish` | `output` | |
+//!
+//! A response `{"error": "message"}` fails the build.
+
+use crate::parser::{BookConfig, FrontMatter};
+use anyhow::{bail, Context as _, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Version of the JSON protocol, sent with `init`
+const PROTOCOL_VERSION: u32 = 1;
+
+/// Directory of a book holding plugin executables
+const PLUGINS_DIR: &str = "plugins";
+
+/// Prefix of plugin executables looked up on the PATH
+const EXECUTABLE_PREFIX: &str = "guidebook-plugin-";
+
+/// Output directory of plugin assets, relative to the book output
+const ASSETS_DIR: &str = "gitbook/plugins";
+
+/// How long a plugin may take to exit once its stdin is closed
+const EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The page a hook is called for
+#[derive(Debug, Serialize)]
+pub struct PageInfo<'a> {
+    /// Source file, relative to the book root (e.g. "guide/intro.md")
+    pub path: &'a str,
+    /// Output file, relative to the output root (e.g. "guide/intro.html")
+    pub output: &'a str,
+    pub title: &'a str,
+    pub front_matter: Option<&'a FrontMatter>,
+}
+
+/// A file a plugin adds to the output. `.css` and `.js` files are linked from every page
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginAsset {
+    pub name: String,
+    pub content: String,
+}
+
+/// What plugins add to a page template
+#[derive(Debug, Default, Serialize)]
+pub struct PluginPage {
+    /// Template variables by plugin name
+    pub context: Map<String, Value>,
+    /// Stylesheets, relative to the output root
+    pub styles: Vec<String>,
+    /// Scripts, relative to the output root
+    pub scripts: Vec<String>,
+}
+
+/// Hooks of a plugin. Each has a default that changes nothing.
+pub trait Plugin {
+    fn name(&self) -> &str;
+
+    /// Change a page's Markdown before it is rendered
+    fn pre_markdown(&mut self, _page: &PageInfo, markdown: String) -> Result<String> {
+        Ok(markdown)
+    }
+
+    /// Change a page's rendered HTML (before it is put in the page template)
+    fn post_html(&mut self, _page: &PageInfo, html: String) -> Result<String> {
+        Ok(html)
+    }
+
+    /// Template variables for a page
+    fn page_context(&mut self, _page: &PageInfo) -> Result<Option<Value>> {
+        Ok(None)
+    }
+
+    /// Files to add to the output
+    fn assets(&mut self) -> Result<Vec<PluginAsset>> {
+        Ok(Vec::new())
+    }
+
+    /// Called once the book is built
+    fn finish(&mut self, _output: &Path) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The plugins enabled for a book
+#[derive(Default)]
+pub struct Plugins {
+    plugins: Vec<Box<dyn Plugin>>,
+    /// Assets by plugin name
+    assets: Vec<(String, PluginAsset)>,
+}
+
+impl Plugins {
+    /// Start the external plugins listed in book.json. `book_dirs` are searched
+    /// in order for `plugins/<name>` and relative commands.
+    pub fn load(book_dirs: &[&Path], config: &BookConfig) -> Result<Self> {
+        let mut plugins = Self::default();
+
+        for name in config.external_plugins() {
+            let options = config.plugins_config.get(name).cloned().unwrap_or(Value::Null);
+            // Missing plugins are listed by the compatibility report
+            let Some((program, args)) = find_executable(name, &options, book_dirs)? else {
+                continue;
+            };
+
+            let book_root = book_dirs.first().copied().unwrap_or(Path::new("."));
+            let plugin = ExternalPlugin::start(name, &program, &args, book_root, options, config)?;
+            println!("  Loaded plugin {} ({})", name, program.display());
+            plugins.add(Box::new(plugin))?;
+        }
+
+        Ok(plugins)
+    }
+
+    /// Add a plugin, collecting its assets
+    pub fn add(&mut self, mut plugin: Box<dyn Plugin>) -> Result<()> {
+        for asset in plugin.assets()? {
+            if !is_safe_relative_path(&asset.name) {
+                bail!("Plugin {} returned an invalid asset name {:?}", plugin.name(), asset.name);
+            }
+            self.assets.push((plugin.name().to_string(), asset));
+        }
+        self.plugins.push(plugin);
+        Ok(())
+    }
+
+    pub fn pre_markdown(&mut self, page: &PageInfo, mut markdown: String) -> Result<String> {
+        for plugin in &mut self.plugins {
+            markdown = plugin.pre_markdown(page, markdown)?;
+        }
+        Ok(markdown)
+    }
+
+    pub fn post_html(&mut self, page: &PageInfo, mut html: String) -> Result<String> {
+        for plugin in &mut self.plugins {
+            html = plugin.post_html(page, html)?;
+        }
+        Ok(html)
+    }
+
+    /// Template variables and asset links of a page
+    pub fn page(&mut self, page: &PageInfo) -> Result<PluginPage> {
+        let mut result = PluginPage::default();
+        for plugin in &mut self.plugins {
+            if let Some(context) = plugin.page_context(page)? {
+                result.context.insert(plugin.name().to_string(), context);
+            }
+        }
+        for (plugin, asset) in &self.assets {
+            let url = format!("{}/{}/{}", ASSETS_DIR, plugin, asset.name);
+            if asset.name.ends_with(".css") {
+                result.styles.push(url);
+            } else if asset.name.ends_with(".js") {
+                result.scripts.push(url);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Write the plugin assets into the output
+    pub fn write_assets(&self, output: &Path) -> Result<()> {
+        for (plugin, asset) in &self.assets {
+            let path = output.join(ASSETS_DIR).join(plugin).join(&asset.name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, &asset.content)?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(&mut self, output: &Path) -> Result<()> {
+        for plugin in &mut self.plugins {
+            plugin.finish(output)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check if an external plugin can be started
+pub fn is_available(name: &str, config: &BookConfig, book_dirs: &[&Path]) -> bool {
+    let options = config.plugins_config.get(name).cloned().unwrap_or(Value::Null);
+    matches!(find_executable(name, &options, book_dirs), Ok(Some(_)))
+}
+
+/// Find the executable of an external plugin and its arguments
+fn find_executable(name: &str, options: &Value, book_dirs: &[&Path]) -> Result<Option<(PathBuf, Vec<String>)>> {
+    // The name becomes a path component of plugins/<name> and of its asset directory
+    if !is_plugin_name(name) {
+        bail!("Invalid plugin name {:?}: it must be a plain name without path separators", name);
+    }
+    let args = match options.get("args") {
+        None => Vec::new(),
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|value| value.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()
+            .with_context(|| format!("pluginsConfig.{}.args must be a list of strings", name))?,
+        Some(_) => bail!("pluginsConfig.{}.args must be a list of strings", name),
+    };
+
+    if let Some(command) = options.get("command") {
+        let command = command
+            .as_str()
+            .with_context(|| format!("pluginsConfig.{}.command must be a string", name))?;
+        // Paths are relative to the book; bare names are looked up on the PATH
+        if command.contains('/') || command.contains('\\') {
+            let path = book_dirs
+                .iter()
+                .map(|dir| dir.join(command))
+                .find(|path| path.is_file())
+                .with_context(|| format!("Plugin {}: command {:?} not found", name, command))?;
+            return Ok(Some((path, args)));
+        }
+        return Ok(Some((PathBuf::from(command), args)));
+    }
+
+    let local = book_dirs
+        .iter()
+        .map(|dir| dir.join(PLUGINS_DIR).join(name))
+        .find(|path| path.is_file());
+    if let Some(path) = local {
+        return Ok(Some((path, args)));
+    }
+
+    Ok(find_in_path(&format!("{}{}", EXECUTABLE_PREFIX, name)).map(|path| (path, args)))
+}
+
+/// Look up a program in the directories of PATH
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths).find_map(|dir| {
+        let path = dir.join(program);
+        if path.is_file() {
+            return Some(path);
+        }
+        let exe = path.with_extension("exe");
+        exe.is_file().then_some(exe)
+    })
+}
+
+/// Check that an asset name stays inside the plugin's asset directory
+fn is_safe_relative_path(name: &str) -> bool {
+    let path = Path::new(name);
+    !name.is_empty() && path.components().all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// Check that a plugin name is a single path segment
+fn is_plugin_name(name: &str) -> bool {
+    is_safe_relative_path(name) && !name.contains(['/', '\\'])
+}
+
+/// A plugin running as a separate process
+struct ExternalPlugin {
+    name: String,
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+    hooks: Vec<String>,
+}
+
+impl ExternalPlugin {
+    fn start(
+        name: &str,
+        program: &Path,
+        args: &[String],
+        book_root: &Path,
+        options: Value,
+        config: &BookConfig,
+    ) -> Result<Self> {
+        let mut child = Command::new(program)
+            .args(args)
+            .current_dir(book_root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| format!("Failed to start plugin {} ({})", name, program.display()))?;
+
+        let stdin = child.stdin.take();
+        let stdout = BufReader::new(child.stdout.take().context("Plugin stdout is not available")?);
+        let mut plugin = Self {
+            name: name.to_string(),
+            child,
+            stdin,
+            stdout,
+            hooks: Vec::new(),
+        };
+
+        let response = plugin.call(json!({
+            "hook": "init",
+            "version": PROTOCOL_VERSION,
+            "name": name,
+            "config": options,
+            "book": {
+                "root": book_root,
+                "title": config.title,
+                "language": config.html_lang(),
+                "variables": config.variables,
+            },
+        }))?;
+        plugin.hooks = match response.get("hooks") {
+            Some(hooks) => serde_json::from_value(hooks.clone())
+                .with_context(|| format!("Plugin {}: \"hooks\" must be a list of hook names", name))?,
+            None => Vec::new(),
+        };
+
+        Ok(plugin)
+    }
+
+    fn handles(&self, hook: &str) -> bool {
+        self.hooks.iter().any(|h| h == hook)
+    }
+
+    /// Send one request and read its response
+    fn call(&mut self, request: Value) -> Result<Value> {
+        let hook = request["hook"].as_str().unwrap_or_default().to_string();
+        let stdin = self.stdin.as_mut().context("Plugin stdin is closed")?;
+        writeln!(stdin, "{}", request)
+            .and_then(|_| stdin.flush())
+            .with_context(|| format!("Plugin {} stopped before the {} hook", self.name, hook))?;
+
+        let mut line = String::new();
+        let read = self.stdout.read_line(&mut line)?;
+        if read == 0 {
+            bail!("Plugin {} exited without answering the {} hook", self.name, hook);
+        }
+        let response: Value = serde_json::from_str(&line)
+            .with_context(|| format!("Plugin {} sent invalid JSON for the {} hook", self.name, hook))?;
+        if let Some(error) = response.get("error") {
+            bail!("Plugin {} failed in the {} hook: {}", self.name, hook, error.as_str().unwrap_or(&error.to_string()));
+        }
+        Ok(response)
+    }
+
+    /// Call a hook that returns page content, keeping the content when none comes back
+    fn call_content(&mut self, hook: &str, page: &PageInfo, content: String) -> Result<String> {
+        if !self.handles(hook) {
+            return Ok(content);
+        }
+        let response = self.call(json!({ "hook": hook, "page": page, "content": content }))?;
+        match response.get("content") {
+            Some(Value::String(changed)) => Ok(changed.clone()),
+            Some(_) => bail!("Plugin {}: \"content\" of the {} hook must be a string", self.name, hook),
+            None => Ok(content),
+        }
+    }
+}
+
+impl Plugin for ExternalPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn pre_markdown(&mut self, page: &PageInfo, markdown: String) -> Result<String> {
+        self.call_content("pre-markdown", page, markdown)
+    }
+
+    fn post_html(&mut self, page: &PageInfo, html: String) -> Result<String> {
+        self.call_content("post-html", page, html)
+    }
+
+    fn page_context(&mut self, page: &PageInfo) -> Result<Option<Value>> {
+        if !self.handles("page-context") {
+            return Ok(None);
+        }
+        let response = self.call(json!({ "hook": "page-context", "page": page }))?;
+        Ok(response.get("context").cloned())
+    }
+
+    fn assets(&mut self) -> Result<Vec<PluginAsset>> {
+        if !self.handles("assets") {
+            return Ok(Vec::new());
+        }
+        let response = self.call(json!({ "hook": "assets" }))?;
+        match response.get("assets") {
+            Some(assets) => serde_json::from_value(assets.clone())
+                .with_context(|| format!("Plugin {}: \"assets\" must be a list of {{name, content}}", self.name)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn finish(&mut self, output: &Path) -> Result<()> {
+        if self.handles("finish") {
+            self.call(json!({ "hook": "finish", "output": output }))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ExternalPlugin {
+    fn drop(&mut self) {
+        // Closing stdin tells the plugin to exit
+        self.stdin.take();
+        let deadline = Instant::now() + EXIT_TIMEOUT;
+        while Instant::now() < deadline {
+            match self.child.try_wait() {
+                Ok(None) => thread::sleep(Duration::from_millis(10)),
+                _ => return,
+            }
+        }
+        println!("  Warning: plugin {} didn't exit after its input was closed; stopping it", self.name);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_asset_names() {
+        assert!(is_safe_relative_path("style.css"));
+        assert!(is_safe_relative_path("fonts/icons.woff"));
+        assert!(!is_safe_relative_path(""));
+        assert!(!is_safe_relative_path("../style.css"));
+        assert!(!is_safe_relative_path("/etc/passwd"));
+
+        assert!(is_plugin_name("word-count"));
+        assert!(!is_plugin_name("tools/word-count"));
+        assert!(!is_plugin_name("../../x"));
+        assert!(!is_plugin_name("..\\x"));
+        assert!(!is_plugin_name(".."));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_plugin() {
+        use crate::testing::TempDir;
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = TempDir::new("plugins");
+        let dir = temp.path();
+        fs::create_dir_all(dir.join(PLUGINS_DIR)).unwrap();
+        let script = dir.join(PLUGINS_DIR).join("shout");
+        fs::write(&script, r##"#!/bin/sh
+while read -r line; do
+    case "$line" in
+        *'"hook":"init"'*) echo '{"hooks":["pre-markdown","page-context","assets"]}' ;;
+        *'"hook":"pre-markdown"'*) echo '{"content":"# SHOUTED"}' ;;
+        *'"hook":"page-context"'*) echo '{"context":{"loud":true}}' ;;
+        *'"hook":"assets"'*) echo '{"assets":[{"name":"shout.css","content":"h1 { color: red; }"}]}' ;;
+        *) echo '{"error":"unexpected hook"}' ;;
+    esac
+done
+"##).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let config: BookConfig = serde_json::from_str(r#"{"plugins": ["shout", "missing"]}"#).unwrap();
+        let mut plugins = Plugins::load(&[dir], &config).unwrap();
+        assert!(is_available("shout", &config, &[dir]));
+        assert!(!is_available("missing", &config, &[dir]));
+        let page = PageInfo { path: "intro.md", output: "intro.html", title: "Intro", front_matter: None };
+
+        assert_eq!(plugins.pre_markdown(&page, "# quiet".to_string()).unwrap(), "# SHOUTED");
+        // post-html isn't implemented by the plugin, so the content stays
+        assert_eq!(plugins.post_html(&page, "<p>x</p>".to_string()).unwrap(), "<p>x</p>");
+
+        let page_data = plugins.page(&page).unwrap();
+        assert_eq!(page_data.context["shout"]["loud"], true);
+        assert_eq!(page_data.styles, ["gitbook/plugins/shout/shout.css"]);
+        assert!(page_data.scripts.is_empty());
+
+        plugins.write_assets(&dir.join("_book")).unwrap();
+        let css = fs::read_to_string(dir.join("_book/gitbook/plugins/shout/shout.css")).unwrap();
+        assert_eq!(css, "h1 { color: red; }");
+
+        // Hooks the plugin didn't ask for are never sent
+        plugins.finish(&dir.join("_book")).unwrap();
+
+        // Names are looked up inside plugins/ only
+        let config: BookConfig = serde_json::from_str(r#"{"plugins": ["../plugins/shout"]}"#).unwrap();
+        let err = Plugins::load(&[dir], &config).err().unwrap().to_string();
+        assert!(err.contains("Invalid plugin name"), "{}", err);
+    }
+}
@@ -38,7 +38,7 @@ pub fn extract_headings(content: &str) -> Vec<TocItem> {
             Event::End(TagEnd::Heading(level)) if in_heading.is_some() => {
                 let level_num = heading_level_to_num(*level);
                 // Only include h2, h3, h4 in TOC (skip h1 which is page title)
-                if level_num >= 2 && level_num <= 4 {
+                if (2..=4).contains(&level_num) {
                     let id = slugify(&heading_text);
                     headings.push(TocItem {
                         level: level_num,
@@ -59,7 +59,7 @@ pub fn extract_headings(content: &str) -> Vec<TocItem> {
 /// current_path: the path of the current markdown file (e.g., "Customer/AssetStatus/PortfolioTop.md")
 /// hardbreaks: when true, treat single newlines as hard breaks (<br>)
 pub fn render_markdown_with_path(content: &str, current_path: Option<&str>, hardbreaks: bool) -> String {
-    let html = render_markdown_internal(content, hardbreaks);
+    let html = render_markdown_internal(content, hardbreaks, "");
 
     // If we have a current path, convert relative links to absolute
     if let Some(path) = current_path {
@@ -70,16 +70,24 @@ pub fn render_markdown_with_path(content: &str, current_path: Option<&str>, hard
 }
 
 /// Render markdown content to HTML (backward compatible)
+#[cfg(test)]
 pub fn render_markdown(content: &str) -> String {
-    render_markdown_internal(content, false)
+    render_markdown_internal(content, false, "")
 }
 
 /// Render markdown content to HTML with hardbreaks option
 pub fn render_markdown_with_hardbreaks(content: &str, hardbreaks: bool) -> String {
-    render_markdown_internal(content, hardbreaks)
+    render_markdown_internal(content, hardbreaks, "")
+}
+
+/// Render markdown inserted into other pages (e.g. glossary definitions).
+/// `id_prefix` starts the ids of generated elements such as tab sets, so
+/// they don't clash with the ids of the page it is inserted into.
+pub fn render_markdown_fragment(content: &str, hardbreaks: bool, id_prefix: &str) -> String {
+    render_markdown_internal(content, hardbreaks, id_prefix)
 }
 
-fn render_markdown_internal(content: &str, hardbreaks: bool) -> String {
+fn render_markdown_internal(content: &str, hardbreaks: bool, id_prefix: &str) -> String {
     // Preprocess: fix full-width spaces after heading markers
     let content = fix_fullwidth_heading_spaces(content);
     // Preprocess: fix image paths with spaces
@@ -88,6 +96,8 @@ fn render_markdown_internal(content: &str, hardbreaks: bool) -> String {
     let content = fix_multiline_footnotes(&content);
     // Preprocess: fix malformed table separator rows
     let content = fix_table_separator_columns(&content);
+    // Preprocess: convert ::: containers and HonKit hints to callouts
+    let content = convert_containers(&content, id_prefix);
 
     // Convert footnote definitions to inline format (preserve original position)
     let content = convert_footnote_definitions_inline(&content, hardbreaks);
@@ -182,6 +192,7 @@ fn render_markdown_internal(content: &str, hardbreaks: bool) -> String {
         }
         events.push(event);
     }
+    let events = convert_blockquote_callouts(events);
 
     let mut html_output = String::new();
     html::push_html(&mut html_output, events.into_iter());
@@ -236,7 +247,7 @@ fn slugify(text: &str) -> String {
         .join("-")
 }
 
-fn html_escape(s: &str) -> String {
+pub(crate) fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -541,11 +552,7 @@ fn count_table_columns(line: &str) -> usize {
     let pipe_count = trimmed.chars().filter(|&c| c == '|').count();
 
     // Number of columns = pipes - 1 (for |col1|col2|col3| format)
-    if pipe_count > 1 {
-        pipe_count - 1
-    } else {
-        0
-    }
+    pipe_count.saturating_sub(1)
 }
 
 /// Check if a line is a table separator row (contains only |, -, :, and whitespace)
@@ -578,7 +585,7 @@ fn generate_separator_row(col_count: usize, original: &str) -> String {
         "--"
     };
 
-    let cols: Vec<&str> = std::iter::repeat(alignment).take(col_count).collect();
+    let cols: Vec<&str> = std::iter::repeat_n(alignment, col_count).collect();
     format!("|{}|", cols.join("|"))
 }
 
@@ -610,6 +617,453 @@ fn fix_fullwidth_heading_spaces(content: &str) -> String {
         .join("\n")
 }
 
+/// Callout kinds and their default titles
+const CALLOUT_KINDS: &[(&str, &str)] = &[
+    ("note", "Note"),
+    ("tip", "Tip"),
+    ("important", "Important"),
+    ("warning", "Warning"),
+    ("caution", "Caution"),
+    ("danger", "Danger"),
+];
+
+/// Callout kind for a marker name, including HonKit hint styles ("info", "success")
+fn callout_kind(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    let name = match name.as_str() {
+        "info" => "note",
+        "hint" | "success" => "tip",
+        "error" => "danger",
+        other => other,
+    };
+    CALLOUT_KINDS.iter().find(|(kind, _)| *kind == name).map(|(kind, _)| *kind)
+}
+
+/// Default (English) title of a callout kind
+pub(crate) fn callout_title(kind: &str) -> Option<&'static str> {
+    CALLOUT_KINDS.iter().find(|(k, _)| *k == kind).map(|(_, title)| *title)
+}
+
+/// Opening markup of a callout, closed by `</div>`
+fn callout_open(kind: &str, title: Option<&str>) -> String {
+    let title = title.or(callout_title(kind)).unwrap_or(kind);
+    format!(
+        "<div class=\"callout callout-{}\" role=\"note\">\n<p class=\"callout-title\">{}</p>\n",
+        kind,
+        html_escape(title)
+    )
+}
+
+/// How an open container is closed
+enum ContainerClose {
+    /// `:::` with at least this many colons
+    Colons(usize),
+    /// `{% endhint %}`
+    EndHint,
+    /// `{% endtabs %}`
+    EndTabs,
+}
+
+/// What an open container is rendered as
+enum ContainerKind {
+    /// Unknown `:::` container, kept as text
+    Text,
+    Callout,
+    Tabs(TabSet),
+}
+
+/// An open container
+struct Container {
+    close: ContainerClose,
+    indent: String,
+    kind: ContainerKind,
+}
+
+/// A tab set being converted
+struct TabSet {
+    /// Id of the set, e.g. "tabs-1"; its tabs and panels are "tabs-1-0" and "tabs-1-0-panel"
+    id: String,
+    /// Whether the tabs are the code blocks of a `::: code-group`
+    code_group: bool,
+    titles: Vec<String>,
+    /// Line replaced by the tab list once all titles are known
+    list_line: usize,
+    panel_open: bool,
+}
+
+impl TabSet {
+    /// Start a tab panel, closing the previous one
+    fn open_panel(&mut self, lines: &mut Vec<String>, indent: &str, title: &str) {
+        self.close_panel(lines, indent);
+        let tab = self.titles.len();
+        lines.push(String::new());
+        lines.push(format!(
+            "{}<div class=\"tab-panel\" role=\"tabpanel\" id=\"{}-{}-panel\" aria-labelledby=\"{}-{}\" tabindex=\"0\"{}>",
+            indent,
+            self.id,
+            tab,
+            self.id,
+            tab,
+            if tab == 0 { "" } else { " hidden" }
+        ));
+        lines.push(format!("{}<p class=\"tab-title\">{}</p>", indent, html_escape(title)));
+        lines.push(String::new());
+        self.titles.push(title.to_string());
+        self.panel_open = true;
+    }
+
+    fn close_panel(&mut self, lines: &mut Vec<String>, indent: &str) {
+        if self.panel_open {
+            lines.push(String::new());
+            lines.push(format!("{}</div>", indent));
+            self.panel_open = false;
+        }
+    }
+
+    /// Tab list with a button per panel; the first tab is selected
+    fn tab_list(&self, indent: &str) -> String {
+        let mut html = format!("{}<div class=\"tabs-list\" role=\"tablist\">", indent);
+        for (tab, title) in self.titles.iter().enumerate() {
+            html.push_str(&format!(
+                "\n{}<button type=\"button\" role=\"tab\" id=\"{}-{}\" aria-controls=\"{}-{}-panel\" aria-selected=\"{}\" tabindex=\"{}\">{}</button>",
+                indent,
+                self.id,
+                tab,
+                self.id,
+                tab,
+                tab == 0,
+                if tab == 0 { 0 } else { -1 },
+                html_escape(title)
+            ));
+        }
+        html.push_str(&format!("\n{}</div>", indent));
+        html
+    }
+}
+
+/// Convert containers to HTML blocks:
+/// - `::: warning Title` … `:::` and HonKit `{% hint style="info" %}` …
+///   `{% endhint %}` become callouts
+/// - `::: tabs` with a `== Title` line per tab, and GitBook `{% tabs %}`
+///   with `{% tab title="Title" %}` … `{% endtab %}`, become tab sets
+/// - `::: code-group` becomes a tab set with a tab per code block, titled
+///   by a `[Title]` after the code block language
+///
+/// The opening and closing tags are written with blank lines around them,
+/// so the content between them is still parsed as markdown and containers
+/// can be nested. Code blocks are left alone. Tab sets are numbered in
+/// the page, with ids starting with `id_prefix`.
+fn convert_containers(content: &str, id_prefix: &str) -> String {
+    let mut lines = Vec::new();
+    // Open containers, innermost last
+    let mut open: Vec<Container> = Vec::new();
+    let mut tab_sets = 0;
+    // Fence character and length of the open code block
+    let mut code_fence: Option<(char, usize)> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+
+        if let Some((marker, len)) = code_fence {
+            lines.push(line.to_string());
+            let fence = trimmed.trim_end();
+            if fence.len() >= len && fence.chars().all(|c| c == marker) {
+                code_fence = None;
+                if let Some(Container { kind: ContainerKind::Tabs(tabs), indent, .. }) = open.last_mut() {
+                    if tabs.code_group {
+                        tabs.close_panel(&mut lines, indent);
+                    }
+                }
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            let marker = if trimmed.starts_with('`') { '`' } else { '~' };
+            let len = trimmed.chars().take_while(|&c| c == marker).count();
+            code_fence = Some((marker, len));
+            if let Some(Container { kind: ContainerKind::Tabs(tabs), indent, .. }) = open.last_mut() {
+                if tabs.code_group {
+                    let (info, title) = split_code_title(&trimmed[len..]);
+                    tabs.open_panel(&mut lines, indent, title);
+                    lines.push(format!("{}{}{}", indent, &trimmed[..len], info));
+                    continue;
+                }
+            }
+            lines.push(line.to_string());
+            continue;
+        }
+
+        if let Some(colons) = parse_container_close(trimmed) {
+            if let Some(Container { close: ContainerClose::Colons(open_colons), .. }) = open.last() {
+                if colons >= *open_colons {
+                    let container = open.pop().unwrap();
+                    if matches!(container.kind, ContainerKind::Text) {
+                        lines.push(line.to_string());
+                    }
+                    close_container(&mut lines, container);
+                    continue;
+                }
+            }
+        } else if let Some((colons, name, title)) = parse_container_open(trimmed) {
+            let close = ContainerClose::Colons(colons);
+            let kind = if name.eq_ignore_ascii_case("tabs") || name.eq_ignore_ascii_case("code-group") {
+                tab_sets += 1;
+                let id = format!("{}tabs-{}", html_escape(id_prefix), tab_sets);
+                open_tabs(&mut lines, indent, id, name.eq_ignore_ascii_case("code-group"))
+            } else if let Some(kind) = callout_kind(name) {
+                push_callout_open(&mut lines, indent, kind, (!title.is_empty()).then_some(title));
+                ContainerKind::Callout
+            } else {
+                lines.push(line.to_string());
+                ContainerKind::Text
+            };
+            open.push(Container { close, indent: indent.to_string(), kind });
+            continue;
+        } else if let Some(title) = trimmed.strip_prefix("== ") {
+            if let Some(Container { close: ContainerClose::Colons(_), kind: ContainerKind::Tabs(tabs), indent }) = open.last_mut() {
+                if !tabs.code_group {
+                    tabs.open_panel(&mut lines, indent, title.trim());
+                    continue;
+                }
+            }
+        } else if let Some(attributes) = parse_template_tag(trimmed, "hint") {
+            let style = template_attribute(attributes, "style").unwrap_or("info");
+            push_callout_open(&mut lines, indent, callout_kind(style).unwrap_or("note"), None);
+            open.push(Container { close: ContainerClose::EndHint, indent: indent.to_string(), kind: ContainerKind::Callout });
+            continue;
+        } else if parse_template_tag(trimmed, "tabs").is_some() {
+            tab_sets += 1;
+            let id = format!("{}tabs-{}", html_escape(id_prefix), tab_sets);
+            let kind = open_tabs(&mut lines, indent, id, false);
+            open.push(Container { close: ContainerClose::EndTabs, indent: indent.to_string(), kind });
+            continue;
+        } else if let Some(attributes) = parse_template_tag(trimmed, "tab") {
+            if let Some(Container { close: ContainerClose::EndTabs, kind: ContainerKind::Tabs(tabs), indent }) = open.last_mut() {
+                let title = template_attribute(attributes, "title").unwrap_or_default();
+                tabs.open_panel(&mut lines, indent, title);
+                continue;
+            }
+        } else if parse_template_tag(trimmed, "endtab").is_some() {
+            // Panels are closed by the next tab or the end of the tab set
+            if let Some(Container { close: ContainerClose::EndTabs, .. }) = open.last() {
+                continue;
+            }
+        } else if let Some(end) = ["endhint", "endtabs"].into_iter().find(|end| parse_template_tag(trimmed, end).is_some()) {
+            let closes = match open.last() {
+                Some(Container { close: ContainerClose::EndHint, .. }) => end == "endhint",
+                Some(Container { close: ContainerClose::EndTabs, .. }) => end == "endtabs",
+                _ => false,
+            };
+            if closes {
+                let container = open.pop().unwrap();
+                close_container(&mut lines, container);
+                continue;
+            }
+        }
+        lines.push(line.to_string());
+    }
+
+    // Close containers left open at the end of the page
+    while let Some(container) = open.pop() {
+        close_container(&mut lines, container);
+    }
+
+    lines.join("\n")
+}
+
+fn push_callout_open(lines: &mut Vec<String>, indent: &str, kind: &str, title: Option<&str>) {
+    lines.push(String::new());
+    for tag_line in callout_open(kind, title).lines() {
+        lines.push(format!("{}{}", indent, tag_line));
+    }
+    lines.push(String::new());
+}
+
+/// Start a tab set; its tab list is written when it is closed
+fn open_tabs(lines: &mut Vec<String>, indent: &str, id: String, code_group: bool) -> ContainerKind {
+    lines.push(String::new());
+    lines.push(format!("{}<div class=\"tabs\">", indent));
+    lines.push(String::new());
+    let tabs = TabSet {
+        id,
+        code_group,
+        titles: Vec::new(),
+        list_line: lines.len() - 1,
+        panel_open: false,
+    };
+    lines.push(String::new());
+    ContainerKind::Tabs(tabs)
+}
+
+fn close_container(lines: &mut Vec<String>, container: Container) {
+    let indent = container.indent;
+    match container.kind {
+        ContainerKind::Text => return,
+        ContainerKind::Callout => {}
+        ContainerKind::Tabs(mut tabs) => {
+            tabs.close_panel(lines, &indent);
+            lines[tabs.list_line] = tabs.tab_list(&indent);
+        }
+    }
+    lines.push(String::new());
+    lines.push(format!("{}</div>", indent));
+    lines.push(String::new());
+}
+
+/// Split the `[Title]` off the info string of a code block in a code group.
+/// Without one, the tab is titled by the language.
+fn split_code_title(info: &str) -> (String, &str) {
+    if let (Some(start), Some(end)) = (info.find('['), info.rfind(']')) {
+        if start < end {
+            let rest = format!("{}{}", &info[..start], &info[end + 1..]);
+            return (rest.trim_end().to_string(), info[start + 1..end].trim());
+        }
+    }
+    let language = info.split_whitespace().next().unwrap_or("");
+    (info.to_string(), if language.is_empty() { "Code" } else { language })
+}
+
+/// Parse `::: name title` into the number of colons, the name and the title
+fn parse_container_open(line: &str) -> Option<(usize, &str, &str)> {
+    let colons = line.chars().take_while(|&c| c == ':').count();
+    if colons < 3 {
+        return None;
+    }
+    let rest = line[colons..].trim();
+    let name_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+    let name = &rest[..name_end];
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return None;
+    }
+    Some((colons, name, rest[name_end..].trim()))
+}
+
+/// Number of colons of a closing `:::` line
+fn parse_container_close(line: &str) -> Option<usize> {
+    let line = line.trim_end();
+    (line.len() >= 3 && line.chars().all(|c| c == ':')).then_some(line.len())
+}
+
+/// Attributes of a `{% name attributes %}` line
+fn parse_template_tag<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let inner = line.trim_end().strip_prefix("{%")?.strip_suffix("%}")?.trim();
+    let attributes = inner.strip_prefix(name)?;
+    if !attributes.is_empty() && !attributes.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(attributes.trim())
+}
+
+/// Value of `key="value"` (or single quoted, or unquoted) in template tag attributes
+fn template_attribute<'a>(attributes: &'a str, key: &str) -> Option<&'a str> {
+    let mut rest = attributes;
+    while let Some(pos) = rest.find(key) {
+        let before_ok = rest[..pos].chars().last().is_none_or(|c| c.is_whitespace() || c == ',');
+        let after = rest[pos + key.len()..].trim_start();
+        if let Some(value) = after.strip_prefix('=').filter(|_| before_ok) {
+            let value = value.trim_start();
+            return match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..].split(quote).next(),
+                _ => value.split(|c: char| c.is_whitespace() || c == ',').next(),
+            };
+        }
+        rest = &rest[pos + key.len()..];
+    }
+    None
+}
+
+/// Turn blockquotes that start with a GitHub alert marker (`> [!NOTE]`) or a
+/// HonKit callout marker (`> **[warning] Title**`) into callouts
+fn convert_blockquote_callouts(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut output = Vec::with_capacity(events.len());
+    // For each open blockquote, whether it became a callout
+    let mut callouts: Vec<bool> = Vec::new();
+    let mut i = 0;
+
+    while i < events.len() {
+        match &events[i] {
+            Event::Start(Tag::BlockQuote) => {
+                if let Some(marker) = blockquote_callout(&events[i + 1..]) {
+                    output.push(Event::Html(callout_open(marker.kind, marker.title.as_deref()).into()));
+                    if marker.keep_paragraph {
+                        output.push(Event::Start(Tag::Paragraph));
+                    }
+                    callouts.push(true);
+                    i += 1 + marker.consumed;
+                    continue;
+                }
+                callouts.push(false);
+            }
+            Event::End(TagEnd::BlockQuote) => {
+                let callout = callouts.pop() == Some(true);
+                output.push(if callout { Event::Html("</div>\n".into()) } else { events[i].clone() });
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+        output.push(events[i].clone());
+        i += 1;
+    }
+
+    output
+}
+
+/// Callout marker at the start of a blockquote
+struct BlockquoteMarker {
+    kind: &'static str,
+    title: Option<String>,
+    /// Events of the marker line, from the paragraph start
+    consumed: usize,
+    /// Whether the first paragraph has more text after the marker line
+    keep_paragraph: bool,
+}
+
+/// Find a callout marker on the first line of a blockquote, given the events after its start
+fn blockquote_callout(events: &[Event<'_>]) -> Option<BlockquoteMarker> {
+    if !matches!(events.first(), Some(Event::Start(Tag::Paragraph))) {
+        return None;
+    }
+    let strong = matches!(events.get(1), Some(Event::Start(Tag::Strong)));
+    let mut i = if strong { 2 } else { 1 };
+
+    // The marker may be split into several text events ("[", "!NOTE", "]")
+    let mut marker = String::new();
+    while let Some(Event::Text(text)) = events.get(i) {
+        marker.push_str(text);
+        i += 1;
+    }
+    if strong {
+        if !matches!(events.get(i), Some(Event::End(TagEnd::Strong))) {
+            return None;
+        }
+        i += 1;
+    }
+    let keep_paragraph = match events.get(i) {
+        Some(Event::SoftBreak | Event::HardBreak) => true,
+        Some(Event::End(TagEnd::Paragraph)) => false,
+        _ => return None,
+    };
+
+    let marker = marker.trim();
+    let name_start = if strong { 1 } else { 2 };
+    let prefix = if strong { "[" } else { "[!" };
+    if !marker.starts_with(prefix) {
+        return None;
+    }
+    let close = marker.find(']')?;
+    let kind = callout_kind(marker[name_start..close].trim_start_matches('!'))?;
+    let title = marker[close + 1..].trim();
+
+    Some(BlockquoteMarker {
+        kind,
+        title: (!title.is_empty()).then(|| title.to_string()),
+        consumed: i + 1,
+        keep_paragraph,
+    })
+}
+
 /// Fix image paths that contain spaces by wrapping them in angle brackets
 /// Converts ![alt](path with space.png) to ![alt](<path with space.png>)
 fn fix_image_paths_with_spaces(content: &str) -> String {
@@ -718,7 +1172,7 @@ fn autolink_urls(html: &str) -> String {
 
             // Collect the tag
             let mut tag_content = String::new();
-            while let Some((_, ch)) = chars.next() {
+            for (_, ch) in chars.by_ref() {
                 result.push(ch);
                 if ch == '>' {
                     break;
@@ -808,7 +1262,7 @@ fn convert_remaining_markdown_images(html: &str) -> String {
 
             // Collect the tag
             let mut tag_content = String::new();
-            while let Some((_, ch)) = chars.next() {
+            for (_, ch) in chars.by_ref() {
                 result.push(ch);
                 if ch == '>' {
                     break;
@@ -838,7 +1292,7 @@ fn convert_remaining_markdown_images(html: &str) -> String {
             // Collect alt text until ']'
             let mut alt = String::new();
             let mut bracket_depth = 1;
-            while let Some((_, ch)) = chars.next() {
+            for (_, ch) in chars.by_ref() {
                 if ch == '[' {
                     bracket_depth += 1;
                     alt.push(ch);
@@ -860,7 +1314,7 @@ fn convert_remaining_markdown_images(html: &str) -> String {
                 // Collect URL until ')'
                 let mut url = String::new();
                 let mut paren_depth = 1;
-                while let Some((_, ch)) = chars.next() {
+                for (_, ch) in chars.by_ref() {
                     if ch == '(' {
                         paren_depth += 1;
                         url.push(ch);
@@ -1007,6 +1461,77 @@ sequenceDiagram
         assert!(html.contains("sequenceDiagram"));
     }
 
+    #[test]
+    fn test_render_github_alerts() {
+        let md = "> [!NOTE]\n> Useful **information**.\n\n> [!warning] Read this\n>\n> - item\n\n> Plain quote\n\n> [!UNKNOWN]\n> Text\n";
+        let html = render_markdown(md);
+        assert!(html.contains(
+            "<div class=\"callout callout-note\" role=\"note\">\n<p class=\"callout-title\">Note</p>\n<p>Useful <strong>information</strong>.</p>\n</div>"
+        ), "HTML: {}", html);
+        assert!(html.contains("<p class=\"callout-title\">Read this</p>\n<ul>"), "HTML: {}", html);
+        assert_eq!(html.matches("<blockquote>").count(), 2, "HTML: {}", html);
+
+        // HonKit callouts and nested alerts
+        let html = render_markdown("> **[info] For info**\n> Use this.\n\n> [!TIP]\n> > [!CAUTION]\n> > Nested\n");
+        assert!(html.contains("<div class=\"callout callout-note\" role=\"note\">\n<p class=\"callout-title\">For info</p>\n<p>Use this.</p>"), "HTML: {}", html);
+        assert!(html.contains("<p class=\"callout-title\">Tip</p>\n<div class=\"callout callout-caution\""), "HTML: {}", html);
+        assert!(html.contains("</div>\n</div>"), "HTML: {}", html);
+    }
+
+    #[test]
+    fn test_render_containers() {
+        let md = "::: danger Do not\nText with `code`.\n\n:::: tip\n## Inside\n::::\n:::\n\n::: details\nkept\n:::\n\n{% hint style=\"success\" %}\nHint **body**\n{% endhint %}\n\n```\n::: note\n```\n";
+        let html = render_markdown(md);
+        assert!(html.contains("<div class=\"callout callout-danger\" role=\"note\">\n<p class=\"callout-title\">Do not</p>\n<p>Text with <code>code</code>.</p>"), "HTML: {}", html);
+        assert!(html.contains("<p class=\"callout-title\">Tip</p>\n<h2 id=\"inside\">Inside</h2></div>\n</div>"), "HTML: {}", html);
+        // Unknown containers and code blocks are left as they are
+        assert!(html.contains("<p>::: details\nkept\n:::</p>"), "HTML: {}", html);
+        assert!(html.contains("<code>::: note\n</code>"), "HTML: {}", html);
+        assert!(html.contains("<p class=\"callout-title\">Tip</p>\n<p>Hint <strong>body</strong></p>"), "HTML: {}", html);
+    }
+
+    #[test]
+    fn test_render_tabs() {
+        let md = "::: tabs\n== Linux\nRun `make`.\n== Windows\n::: tip\nNested\n:::\n:::\n";
+        let html = render_markdown(md);
+        assert!(html.starts_with(concat!(
+            "<div class=\"tabs\">\n<div class=\"tabs-list\" role=\"tablist\">\n",
+            "<button type=\"button\" role=\"tab\" id=\"tabs-1-0\" aria-controls=\"tabs-1-0-panel\" aria-selected=\"true\" tabindex=\"0\">Linux</button>\n",
+            "<button type=\"button\" role=\"tab\" id=\"tabs-1-1\" aria-controls=\"tabs-1-1-panel\" aria-selected=\"false\" tabindex=\"-1\">Windows</button>\n</div>\n",
+            "<div class=\"tab-panel\" role=\"tabpanel\" id=\"tabs-1-0-panel\" aria-labelledby=\"tabs-1-0\" tabindex=\"0\">\n",
+            "<p class=\"tab-title\">Linux</p>\n<p>Run <code>make</code>.</p>\n</div>\n",
+            "<div class=\"tab-panel\" role=\"tabpanel\" id=\"tabs-1-1-panel\" aria-labelledby=\"tabs-1-1\" tabindex=\"0\" hidden>\n",
+            "<p class=\"tab-title\">Windows</p>\n<div class=\"callout callout-tip\""
+        )), "HTML: {}", html);
+        assert!(html.ends_with("</div>\n</div>\n</div>"), "HTML: {}", html);
+
+        // GitBook tabs
+        let md = "{% tabs %}\n{% tab title=\"First tab\" %}\nOne\n{% endtab %}\n{% tab title='Second' %}\nTwo\n{% endtab %}\n{% endtabs %}\n\n{% tabs %}\n{% tab title=\"Again\" %}\nThree\n{% endtabs %}\n";
+        let html = render_markdown(md);
+        assert!(html.contains("id=\"tabs-1-0\" aria-controls=\"tabs-1-0-panel\" aria-selected=\"true\" tabindex=\"0\">First tab</button>"), "HTML: {}", html);
+        assert!(html.contains("<p class=\"tab-title\">Second</p>\n<p>Two</p>\n</div>\n</div>"), "HTML: {}", html);
+        assert!(html.contains("id=\"tabs-2-0\""), "HTML: {}", html);
+        assert!(!html.contains("{%"), "HTML: {}", html);
+    }
+
+    #[test]
+    fn test_render_code_group() {
+        let md = "::: code-group\n```sh [npm]\nnpm install\n```\n\n~~~js\nlet x;\n~~~\n:::\n";
+        let html = render_markdown(md);
+        assert!(html.contains(">npm</button>\n<button"), "HTML: {}", html);
+        assert!(html.contains(">js</button>"), "HTML: {}", html);
+        assert!(html.contains("<p class=\"tab-title\">npm</p>\n<pre><code class=\"language-sh\">npm install\n</code></pre>\n</div>"), "HTML: {}", html);
+        assert!(html.contains("hidden>\n<p class=\"tab-title\">js</p>\n<pre><code class=\"language-js\">"), "HTML: {}", html);
+    }
+
+    #[test]
+    fn test_template_attribute() {
+        assert_eq!(template_attribute(r#"title="Windows PowerShell""#, "title"), Some("Windows PowerShell"));
+        assert_eq!(template_attribute("style='info'", "style"), Some("info"));
+        assert_eq!(template_attribute("style=danger", "style"), Some("danger"));
+        assert_eq!(template_attribute(r#"subtitle="x""#, "title"), None);
+    }
+
     #[test]
     fn test_fix_relative_links() {
         let html = r#"<a href="chapter1.md">Link</a>"#;
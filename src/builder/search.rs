@@ -0,0 +1,904 @@
+//! Search index generation
+//!
+//! Pages are tokenized in Rust into an inverted index (term → pages) that
+//! `search.js` queries directly. Tokenization depends on the book language:
+//! - CJK text (Japanese, Chinese, Korean) is split into overlapping bigrams,
+//!   since it has no spaces between words. Each character is also indexed on
+//!   its own, so a one-character query only needs that character's shard
+//! - English words are reduced to a common stem ("configured", "configuring"
+//!   → "configur") so different forms of a word match each other
+//!
+//! `search.js` applies the same tokenizer to queries; keep the two in sync.
+//!
+//! Pages are split into sections at their h2-h4 headings, so results can link
+//! straight to `page.html#heading-id`. The index is built from the same
+//! processed HTML as the pages themselves (collected in `SearchPages` during
+//! the build), so front matter, variables and glossary markup are accounted for.
+//!
+//! Output layout, kept small so search works on slow connections:
+//! - `search_index.json`: the manifest (section titles, paths, snippets and
+//!   the list of term shards), fetched when the search box is first focused
+//! - `search_index/<shard>.json`: postings of all terms starting with the same
+//!   character, fetched only when a query needs them
+//! - `search_library.json` (optional, at the output root): the indexes of every
+//!   language and of other builds listed in book.json `search.library`, which
+//!   `search.js` queries together and groups by book and language
+
+use crate::builder::TocItem;
+use crate::parser::glossary::definition_id;
+use crate::parser::{BookConfig, FrontMatter, Glossary, Summary, SummaryItem};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Score of a term occurrence in a page title, relative to one in the body
+const TITLE_WEIGHT: u32 = 10;
+
+/// Score of a front matter keyword
+const KEYWORD_WEIGHT: u32 = TITLE_WEIGHT;
+
+/// Maximum length (in characters) of the text shown under a search result
+const SNIPPET_LENGTH: usize = 160;
+
+/// Directory of the term shards, next to search_index.json
+const SHARD_DIR: &str = "search_index";
+
+/// Size budget of the manifest, fetched before the first search
+const MANIFEST_BUDGET: usize = 1024 * 1024;
+
+/// Size budget of one term shard, fetched per query character
+const SHARD_BUDGET: usize = 256 * 1024;
+
+/// A page or section in the search index
+struct SearchDoc {
+    /// Page title, or section heading
+    title: String,
+    path: String,
+    /// Heading id of a section (None for the top of a page)
+    id: Option<String>,
+    /// Part, parent chapters, page and parent headings leading to this entry
+    breadcrumb: Vec<String>,
+    snippet: String,
+}
+
+/// Serialized form of search_index.json
+#[derive(Serialize)]
+struct SearchManifest<'a> {
+    lang: &'a str,
+    /// Queries must be stemmed like the indexed text
+    stem: bool,
+    /// Distinct breadcrumbs, referenced by index from docs
+    breadcrumbs: Vec<&'a [String]>,
+    /// [title, path, heading id ("" for the top of a page), breadcrumb index, snippet]
+    docs: Vec<(&'a str, &'a str, &'a str, usize, &'a str)>,
+    /// Shard keys with a file in search_index/
+    shards: Vec<String>,
+    /// search_library.json, relative to this index
+    #[serde(skip_serializing_if = "Option::is_none")]
+    library: Option<&'a str>,
+}
+
+/// A search index listed in search_library.json
+#[derive(Debug, Serialize)]
+struct SearchSource {
+    book: String,
+    /// Language code ("" when unknown)
+    language: String,
+    /// Language name shown in result groups ("" when unknown)
+    label: String,
+    /// Directory holding the source's search_index.json, relative to search_library.json
+    url: String,
+}
+
+/// Library file referenced by book.json `search.library`
+#[derive(Debug, Deserialize)]
+struct LibraryFile {
+    books: Vec<LibraryBook>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LibraryBook {
+    title: String,
+    /// Output directory of the other build (containing its search_index.json)
+    url: String,
+    #[serde(default)]
+    language: String,
+    #[serde(default)]
+    label: String,
+}
+
+/// Combined search over the languages of this build and the books of its
+/// library, written to search_library.json at the output root
+#[derive(Debug, Default)]
+pub struct SearchLibrary {
+    /// Sources of this build, searched first
+    own: Vec<SearchSource>,
+    /// Other builds from the library file
+    others: Vec<SearchSource>,
+}
+
+impl SearchLibrary {
+    /// Read the library settings; None unless `search.global` or `search.library` is set
+    pub fn load(source: &Path, config: &BookConfig) -> Result<Option<Self>> {
+        let mut library = Self::default();
+        match &config.search.library {
+            Some(path) => {
+                let library_path = source.join(path);
+                let content = fs::read_to_string(&library_path)
+                    .with_context(|| format!("Failed to read search library {:?}", library_path))?;
+                let file: LibraryFile = serde_json::from_str(&content)
+                    .with_context(|| format!("Invalid search library {:?}", library_path))?;
+                library.others = file
+                    .books
+                    .into_iter()
+                    .map(|book| SearchSource {
+                        book: book.title,
+                        language: book.language,
+                        label: book.label,
+                        url: with_trailing_slash(book.url),
+                    })
+                    .collect();
+            }
+            None if !config.search.global => return Ok(None),
+            None => {}
+        }
+        Ok(Some(library))
+    }
+
+    /// Add an index of this build (`url` is its directory relative to the output root)
+    pub fn add_own(&mut self, book: &str, language: &str, label: &str, url: &str) {
+        self.own.push(SearchSource {
+            book: book.to_string(),
+            language: language.to_string(),
+            label: label.to_string(),
+            url: with_trailing_slash(url.to_string()),
+        });
+    }
+
+    pub fn write(&self, output: &Path) -> Result<()> {
+        #[derive(Serialize)]
+        struct LibraryManifest<'a> {
+            sources: Vec<&'a SearchSource>,
+        }
+
+        let manifest = LibraryManifest {
+            sources: self.own.iter().chain(&self.others).collect(),
+        };
+        fs::write(output.join("search_library.json"), serde_json::to_string(&manifest)?)?;
+        Ok(())
+    }
+}
+
+fn with_trailing_slash(mut url: String) -> String {
+    if !url.is_empty() && !url.ends_with('/') {
+        url.push('/');
+    }
+    url
+}
+
+/// Language-aware tokenizer shared by indexing and (mirrored in search.js) querying
+#[derive(Debug, Clone, Copy)]
+pub struct Tokenizer {
+    stem: bool,
+}
+
+impl Tokenizer {
+    /// Tokenizer for a language code (e.g. "en", "ja-JP")
+    pub fn for_language(lang: &str) -> Self {
+        let primary = lang.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        Self {
+            stem: primary == "en",
+        }
+    }
+
+    /// Split a query into terms, as search.js does
+    #[cfg(test)]
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        self.split(text, false)
+    }
+
+    /// Split text into index terms: the query terms plus every CJK character
+    pub fn index_terms(&self, text: &str) -> Vec<String> {
+        self.split(text, true)
+    }
+
+    fn split(&self, text: &str, cjk_characters: bool) -> Vec<String> {
+        let mut tokens = Vec::new();
+        let mut word = String::new();
+        let mut cjk_run: Vec<char> = Vec::new();
+
+        for c in text.chars() {
+            if is_cjk(c) {
+                self.flush_word(&mut word, &mut tokens);
+                cjk_run.push(c);
+            } else if c.is_alphanumeric() {
+                flush_cjk(&mut cjk_run, &mut tokens, cjk_characters);
+                word.extend(c.to_lowercase());
+            } else {
+                self.flush_word(&mut word, &mut tokens);
+                flush_cjk(&mut cjk_run, &mut tokens, cjk_characters);
+            }
+        }
+        self.flush_word(&mut word, &mut tokens);
+        flush_cjk(&mut cjk_run, &mut tokens, cjk_characters);
+
+        tokens
+    }
+
+    fn flush_word(&self, word: &mut String, tokens: &mut Vec<String>) {
+        if word.is_empty() {
+            return;
+        }
+        let token = if self.stem {
+            stem_english(word)
+        } else {
+            word.clone()
+        };
+        tokens.push(token);
+        word.clear();
+    }
+}
+
+/// Emit bigrams for a run of CJK characters (a lone character is kept as
+/// is), and each character on its own when `characters` is set
+fn flush_cjk(run: &mut Vec<char>, tokens: &mut Vec<String>, characters: bool) {
+    if run.len() == 1 {
+        tokens.push(run[0].to_string());
+    } else {
+        for pair in run.windows(2) {
+            tokens.push(pair.iter().collect());
+        }
+        if characters {
+            tokens.extend(run.iter().map(char::to_string));
+        }
+    }
+    run.clear();
+}
+
+/// Hiragana, Katakana, CJK ideographs and Hangul
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{ff66}'..='\u{ff9f}'
+        | '\u{ac00}'..='\u{d7af}'
+    )
+}
+
+/// Light English stemmer: strips plural, -ing, -ed and trailing -e
+fn stem_english(word: &str) -> String {
+    if word.len() <= 3 || !word.is_ascii() {
+        return word.to_string();
+    }
+
+    let mut stem = word.to_string();
+    if stem.ends_with("sses") {
+        stem.truncate(stem.len() - 2);
+    } else if stem.ends_with("ies") {
+        stem.truncate(stem.len() - 3);
+        stem.push('y');
+    } else if stem.ends_with('s') && !stem.ends_with("ss") && !stem.ends_with("us") && !stem.ends_with("is") {
+        stem.truncate(stem.len() - 1);
+    }
+
+    if stem.ends_with("ing") && stem.len() >= 6 {
+        stem.truncate(stem.len() - 3);
+        undouble(&mut stem);
+    } else if stem.ends_with("ed") && stem.len() >= 5 {
+        stem.truncate(stem.len() - 2);
+        undouble(&mut stem);
+    }
+
+    if stem.ends_with('e') && stem.len() > 4 {
+        stem.truncate(stem.len() - 1);
+    }
+
+    stem
+}
+
+/// "runn" → "run", but keep "ll", "ss" and "zz" ("install", "pass")
+fn undouble(stem: &mut String) {
+    let bytes = stem.as_bytes();
+    let len = bytes.len();
+    if len >= 2 && bytes[len - 1] == bytes[len - 2] && !b"aeioulsz".contains(&bytes[len - 1]) {
+        stem.truncate(len - 1);
+    }
+}
+
+/// Strip HTML tags from content for search indexing
+fn strip_html_tags(html: &str) -> String {
+    let mut result = String::new();
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        if c == '<' {
+            in_tag = true;
+        } else if c == '>' {
+            in_tag = false;
+        } else if !in_tag {
+            result.push(c);
+        }
+    }
+
+    // Clean up whitespace
+    result
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Processed content of a built page, collected for the search index
+pub struct SearchPage {
+    title: String,
+    /// Page HTML after variable expansion and glossary markup
+    html: String,
+    headings: Vec<TocItem>,
+    /// Front matter `keywords`, boosted like the title
+    keywords: Vec<String>,
+}
+
+/// Pages collected while building a book, keyed by output path
+#[derive(Default)]
+pub struct SearchPages {
+    pages: HashMap<String, SearchPage>,
+}
+
+impl SearchPages {
+    /// Record a built page, unless its front matter sets `search: false`
+    pub fn insert(
+        &mut self,
+        html_path: &str,
+        title: &str,
+        html: &str,
+        headings: &[TocItem],
+        front_matter: Option<&FrontMatter>,
+    ) {
+        if front_matter.and_then(|fm| fm.search) == Some(false) {
+            return;
+        }
+        let keywords = front_matter
+            .map(|fm| fm.keywords.clone())
+            .unwrap_or_default();
+        self.pages.insert(
+            html_path.to_string(),
+            SearchPage {
+                title: title.to_string(),
+                html: html.to_string(),
+                headings: headings.to_vec(),
+                keywords,
+            },
+        );
+    }
+}
+
+/// Inverted index under construction
+struct SearchIndex {
+    tokenizer: Tokenizer,
+    docs: Vec<SearchDoc>,
+    index: BTreeMap<String, Vec<[u32; 2]>>,
+    /// Glossary term anchor → its names and definition, indexed wherever the term is used
+    synonyms: HashMap<String, String>,
+    glossary_term: Regex,
+}
+
+impl SearchIndex {
+    fn new(tokenizer: Tokenizer, glossary: &Glossary) -> Self {
+        let synonyms = glossary
+            .terms
+            .iter()
+            .map(|term| {
+                let mut words = vec![term.name.as_str()];
+                words.extend(term.aliases.iter().map(String::as_str));
+                words.push(&term.definition);
+                (definition_id(term), words.join(" "))
+            })
+            .collect();
+        Self {
+            tokenizer,
+            docs: Vec::new(),
+            index: BTreeMap::new(),
+            synonyms,
+            glossary_term: Regex::new(r#"class="glossary-term"[^>]*aria-describedby="([^"]*)""#).unwrap(),
+        }
+    }
+
+    /// Add a page, split into one entry per h2-h4 section
+    fn add_page(&mut self, page: &SearchPage, path: &str, breadcrumb: &[String]) {
+        let mut page_breadcrumb = breadcrumb.to_vec();
+        page_breadcrumb.push(page.title.clone());
+        // Headings enclosing the current section, by level
+        let mut heading_stack: Vec<&TocItem> = Vec::new();
+
+        for (heading, section_html) in split_sections(&page.html, &page.headings) {
+            let text = strip_html_tags(section_html);
+            let synonyms = self.glossary_synonyms(section_html);
+            match heading {
+                None => {
+                    let mut scores = self.score(&page.title, &text, &synonyms);
+                    for keyword in &page.keywords {
+                        for token in self.tokenizer.index_terms(keyword) {
+                            *scores.entry(token).or_default() += KEYWORD_WEIGHT;
+                        }
+                    }
+                    self.push(&page.title, path, None, breadcrumb.to_vec(), &text, scores);
+                }
+                Some(heading) => {
+                    heading_stack.retain(|parent| parent.level < heading.level);
+                    let mut section_breadcrumb = page_breadcrumb.clone();
+                    section_breadcrumb.extend(heading_stack.iter().map(|parent| parent.text.clone()));
+                    heading_stack.push(heading);
+
+                    if !text.is_empty() {
+                        let scores = self.score(&heading.text, &text, &synonyms);
+                        let id = Some(heading.id.clone());
+                        self.push(&heading.text, path, id, section_breadcrumb, &text, scores);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Term scores of an entry: title and body occurrences, plus glossary
+    /// synonyms (counted once, and only if not already in the text)
+    fn score(&self, title: &str, content: &str, synonyms: &str) -> HashMap<String, u32> {
+        let mut scores: HashMap<String, u32> = HashMap::new();
+        for token in self.tokenizer.index_terms(title) {
+            *scores.entry(token).or_default() += TITLE_WEIGHT;
+        }
+        for token in self.tokenizer.index_terms(content) {
+            *scores.entry(token).or_default() += 1;
+        }
+        for token in self.tokenizer.index_terms(synonyms) {
+            scores.entry(token).or_insert(1);
+        }
+        scores
+    }
+
+    /// Names and definitions of the glossary terms marked up in a section
+    fn glossary_synonyms(&self, section_html: &str) -> String {
+        if self.synonyms.is_empty() {
+            return String::new();
+        }
+        let mut used: Vec<&str> = self
+            .glossary_term
+            .captures_iter(section_html)
+            .filter_map(|caps| self.synonyms.get(&caps[1]).map(String::as_str))
+            .collect();
+        used.sort_unstable();
+        used.dedup();
+        used.join(" ")
+    }
+
+    fn push(
+        &mut self,
+        title: &str,
+        path: &str,
+        id: Option<String>,
+        breadcrumb: Vec<String>,
+        content: &str,
+        scores: HashMap<String, u32>,
+    ) {
+        let doc_id = self.docs.len() as u32;
+        for (term, score) in scores {
+            self.index.entry(term).or_default().push([doc_id, score]);
+        }
+
+        self.docs.push(SearchDoc {
+            title: title.to_string(),
+            path: path.to_string(),
+            id,
+            breadcrumb,
+            snippet: make_snippet(content),
+        });
+    }
+
+    /// Write the manifest and term shards, and report their sizes
+    fn write(&self, output: &Path, lang: &str, library: Option<&str>) -> Result<()> {
+        // Shards: term → [doc delta, score, doc delta, score, ...]
+        let mut shards: BTreeMap<String, BTreeMap<&str, Vec<u32>>> = BTreeMap::new();
+        for (term, postings) in &self.index {
+            let mut encoded = Vec::with_capacity(postings.len() * 2);
+            let mut previous_doc = 0;
+            for [doc, score] in postings {
+                encoded.push(doc - previous_doc);
+                encoded.push(*score);
+                previous_doc = *doc;
+            }
+            shards.entry(shard_key(term)).or_default().insert(term, encoded);
+        }
+
+        let shard_dir = output.join(SHARD_DIR);
+        if shard_dir.exists() {
+            fs::remove_dir_all(&shard_dir)?;
+        }
+        fs::create_dir_all(&shard_dir)?;
+
+        let mut shard_sizes = Vec::new();
+        for (key, terms) in &shards {
+            let json = serde_json::to_string(terms)?;
+            fs::write(shard_dir.join(format!("{}.json", key)), &json)?;
+            shard_sizes.push((key.as_str(), json.len()));
+        }
+
+        let mut breadcrumbs: Vec<&[String]> = Vec::new();
+        let mut breadcrumb_ids: HashMap<&[String], usize> = HashMap::new();
+        let docs = self
+            .docs
+            .iter()
+            .map(|doc| {
+                let breadcrumb = *breadcrumb_ids.entry(&doc.breadcrumb).or_insert_with(|| {
+                    breadcrumbs.push(&doc.breadcrumb);
+                    breadcrumbs.len() - 1
+                });
+                let id = doc.id.as_deref().unwrap_or("");
+                (doc.title.as_str(), doc.path.as_str(), id, breadcrumb, doc.snippet.as_str())
+            })
+            .collect();
+
+        let manifest = SearchManifest {
+            lang,
+            stem: self.tokenizer.stem,
+            breadcrumbs,
+            docs,
+            shards: shards.keys().cloned().collect(),
+            library,
+        };
+        let json = serde_json::to_string(&manifest)?;
+        fs::write(output.join("search_index.json"), &json)?;
+
+        report_sizes(self.docs.len(), json.len(), &shard_sizes);
+        Ok(())
+    }
+}
+
+/// Shard of a term, by its first character: ASCII letters and digits get a
+/// shard each, other characters are spread over 64 shards by code point
+fn shard_key(term: &str) -> String {
+    let first = term.chars().next().unwrap_or('_');
+    if first.is_ascii_alphanumeric() {
+        first.to_string()
+    } else {
+        format!("u{:02x}", first as u32 % 64)
+    }
+}
+
+/// Print the index size against the budgets, warning when over
+fn report_sizes(entries: usize, manifest_size: usize, shard_sizes: &[(&str, usize)]) {
+    let shards_total: usize = shard_sizes.iter().map(|(_, size)| size).sum();
+    let (largest_key, largest_size) = shard_sizes
+        .iter()
+        .max_by_key(|(_, size)| *size)
+        .copied()
+        .unwrap_or(("-", 0));
+
+    println!(
+        "  Search index: {} entries, manifest {} (budget {}), {} shards {} (largest {}: {}, budget {})",
+        entries,
+        format_size(manifest_size),
+        format_size(MANIFEST_BUDGET),
+        shard_sizes.len(),
+        format_size(shards_total),
+        largest_key,
+        format_size(largest_size),
+        format_size(SHARD_BUDGET),
+    );
+
+    if manifest_size > MANIFEST_BUDGET {
+        println!("  Warning: search manifest exceeds its size budget; it is loaded before the first search");
+    }
+    if largest_size > SHARD_BUDGET {
+        println!("  Warning: search shard '{}' exceeds its size budget", largest_key);
+    }
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+/// Split page HTML at its h2-h4 headings. The first section (before any
+/// heading) has no heading; heading elements themselves are left out.
+fn split_sections<'a>(html: &'a str, headings: &'a [TocItem]) -> Vec<(Option<&'a TocItem>, &'a str)> {
+    let mut sections = Vec::new();
+    let mut current: Option<&TocItem> = None;
+    let mut start = 0;
+
+    for heading in headings {
+        let open_tag = format!(r#"<h{} id="{}">"#, heading.level, heading.id);
+        let Some(pos) = html[start..].find(&open_tag).map(|pos| start + pos) else {
+            continue;
+        };
+        let close_tag = format!("</h{}>", heading.level);
+        let Some(end) = html[pos..].find(&close_tag).map(|end| pos + end + close_tag.len()) else {
+            continue;
+        };
+
+        sections.push((current, &html[start..pos]));
+        current = Some(heading);
+        start = end;
+    }
+    sections.push((current, &html[start..]));
+
+    sections
+}
+
+/// Leading text of a section, cut at a character boundary
+fn make_snippet(text: &str) -> String {
+    match text.char_indices().nth(SNIPPET_LENGTH) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Add the built pages in SUMMARY.md order, with their part and parent chapters as breadcrumb
+fn collect_search_entries(
+    items: &[SummaryItem],
+    pages: &SearchPages,
+    breadcrumb: &mut Vec<String>,
+    indexed: &mut HashSet<String>,
+    index: &mut SearchIndex,
+) {
+    // Part title of the items that follow it (top level only)
+    let mut part: Option<&str> = None;
+
+    for item in items {
+        match item {
+            SummaryItem::PartTitle(title) => part = Some(title),
+            SummaryItem::Link { title, path, children } => {
+                if let Some(part) = part {
+                    breadcrumb.push(part.to_string());
+                }
+                if let Some(md_path) = path {
+                    // Remove anchor #xxx if present; index each page once
+                    let base_path = md_path.split('#').next().unwrap_or("");
+                    let html_path = base_path.replace(".md", ".html");
+                    if let Some(page) = pages.pages.get(&html_path) {
+                        if indexed.insert(html_path.clone()) {
+                            index.add_page(page, &html_path, breadcrumb);
+                        }
+                    }
+                }
+                if !children.is_empty() {
+                    breadcrumb.push(title.clone());
+                    collect_search_entries(children, pages, breadcrumb, indexed, index);
+                    breadcrumb.pop();
+                }
+                if part.is_some() {
+                    breadcrumb.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Generate the search index from the pages collected during the build.
+/// `library` is the path of search_library.json relative to `output`, if any
+pub fn generate_search_index(
+    output: &Path,
+    summary: &Summary,
+    config: &BookConfig,
+    pages: &SearchPages,
+    glossary: &Glossary,
+    library: Option<&str>,
+) -> Result<()> {
+    let lang = config.html_lang();
+    let mut index = SearchIndex::new(Tokenizer::for_language(lang), glossary);
+    let mut indexed = HashSet::new();
+
+    // Home page (README.md) first
+    if let Some(page) = pages.pages.get("index.html") {
+        indexed.insert("index.html".to_string());
+        index.add_page(page, "index.html", &[]);
+    }
+
+    // Then all chapters
+    collect_search_entries(&summary.items, pages, &mut Vec::new(), &mut indexed, &mut index);
+
+    index.write(output, lang, library)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::extract_headings;
+    use crate::builder::renderer::render_markdown;
+    use crate::parser::glossary::apply_glossary;
+    use crate::parser::parse_front_matter;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_tokenize_words() {
+        let tokens = Tokenizer::for_language("de").tokenize("Hello, World! v2.0");
+        assert_eq!(tokens, vec!["hello", "world", "v2", "0"]);
+    }
+
+    #[test]
+    fn test_tokenize_cjk_bigrams() {
+        let tokens = Tokenizer::for_language("ja").tokenize("検索機能とAPI");
+        assert_eq!(tokens, vec!["検索", "索機", "機能", "能と", "api"]);
+
+        let tokens = Tokenizer::for_language("ja").tokenize("本 です");
+        assert_eq!(tokens, vec!["本", "です"]);
+
+        // The index also has each character, found by one-character queries
+        let terms = Tokenizer::for_language("ja").index_terms("本 です");
+        assert_eq!(terms, vec!["本", "です", "で", "す"]);
+    }
+
+    #[test]
+    fn test_english_stemming() {
+        let tokenizer = Tokenizer::for_language("en-US");
+        assert_eq!(
+            tokenizer.tokenize("configure configured configuring configures"),
+            vec!["configur"; 4]
+        );
+        assert_eq!(tokenizer.tokenize("running runs"), vec!["run", "run"]);
+        assert_eq!(tokenizer.tokenize("libraries library"), vec!["library", "library"]);
+        assert_eq!(tokenizer.tokenize("install installing"), vec!["install", "install"]);
+        assert_eq!(tokenizer.tokenize("status class"), vec!["status", "class"]);
+    }
+
+    #[test]
+    fn test_no_stemming_for_other_languages() {
+        assert_eq!(Tokenizer::for_language("vi").tokenize("running"), vec!["running"]);
+    }
+
+    fn page(title: &str, markdown: &str) -> SearchPage {
+        SearchPage {
+            title: title.to_string(),
+            html: render_markdown(markdown),
+            headings: extract_headings(markdown),
+            keywords: Vec::new(),
+        }
+    }
+
+    fn english_index() -> SearchIndex {
+        SearchIndex::new(Tokenizer::for_language("en"), &Glossary::default())
+    }
+
+    #[test]
+    fn test_index_scores_title_higher() {
+        let mut index = english_index();
+        index.add_page(&page("Search", "Other words"), "search.html", &[]);
+        index.add_page(&page("Other", "search here"), "other.html", &[]);
+
+        let postings = &index.index["search"];
+        assert_eq!(postings, &vec![[0, TITLE_WEIGHT], [1, 1]]);
+    }
+
+    #[test]
+    fn test_every_tab_is_indexed() {
+        let mut index = english_index();
+        index.add_page(&page("Install", "::: tabs\n== Linux\nUse apt.\n== Windows\nUse winget.\n:::\n"), "install.html", &[]);
+
+        assert!(index.index.contains_key("apt"));
+        assert!(index.index.contains_key("winget"));
+        assert!(index.index.contains_key("window"));
+    }
+
+    #[test]
+    fn test_sections_split_at_headings() {
+        let markdown = "# Guide\n\nIntro text.\n\n## Install\n\nRun the installer.\n\n### On Linux\n\nUse apt.\n\n## Usage\n\nRun it.\n";
+
+        let mut index = english_index();
+        index.add_page(&page("Guide", markdown), "guide.html", &["Part 1".to_string()]);
+
+        let docs = &index.docs;
+        assert_eq!(docs.len(), 4);
+        assert_eq!(docs[0].title, "Guide");
+        assert_eq!(docs[0].id, None);
+        assert_eq!(docs[0].breadcrumb, vec!["Part 1"]);
+        assert!(docs[0].snippet.contains("Intro text."));
+        assert!(!docs[0].snippet.contains("installer"));
+
+        assert_eq!(docs[2].title, "On Linux");
+        assert_eq!(docs[2].id.as_deref(), Some("on-linux"));
+        assert_eq!(docs[2].breadcrumb, vec!["Part 1", "Guide", "Install"]);
+        assert_eq!(docs[2].snippet, "Use apt.");
+
+        assert_eq!(docs[3].breadcrumb, vec!["Part 1", "Guide"]);
+    }
+
+    #[test]
+    fn test_snippet_is_truncated() {
+        let text = "あ".repeat(SNIPPET_LENGTH + 10);
+        let snippet = make_snippet(&text);
+        assert_eq!(snippet.chars().count(), SNIPPET_LENGTH + 3);
+        assert!(snippet.ends_with("..."));
+    }
+
+    #[test]
+    fn test_shard_key() {
+        assert_eq!(shard_key("search"), "s");
+        assert_eq!(shard_key("2fa"), "2");
+        // '検' is U+691C
+        assert_eq!(shard_key("検索"), format!("u{:02x}", 0x691C % 64));
+    }
+
+    #[test]
+    fn test_write_sharded_index() {
+        let dir = TempDir::new("search-shards");
+        let output = dir.path();
+
+        let mut index = english_index();
+        let scores = index.score("Alpha", "beta", "");
+        index.push("Alpha", "a.html", None, Vec::new(), "beta", scores);
+        let scores = index.score("Beta", "beta gamma", "");
+        index.push("Beta", "b.html", Some("x".to_string()), vec!["Alpha".to_string()], "beta gamma", scores);
+        index.write(output, "en", None).unwrap();
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output.join("search_index.json")).unwrap()).unwrap();
+        assert_eq!(manifest["shards"], serde_json::json!(["a", "b", "g"]));
+        assert_eq!(manifest["docs"][1], serde_json::json!(["Beta", "b.html", "x", 1, "beta gamma"]));
+        assert_eq!(manifest["breadcrumbs"], serde_json::json!([[], ["Alpha"]]));
+
+        let shard: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output.join("search_index/b.json")).unwrap()).unwrap();
+        // Doc ids are delta-encoded: doc 0 (score 1), doc 1 (title + body)
+        assert_eq!(shard["beta"], serde_json::json!([0, 1, 1, TITLE_WEIGHT + 1]));
+    }
+
+    #[test]
+    fn test_front_matter_controls_indexing() {
+        let parsed = parse_front_matter("---\nsearch: false\n---\n# Hidden\n");
+        let mut pages = SearchPages::default();
+        pages.insert("hidden.html", "Hidden", "<p>Hidden</p>", &[], parsed.front_matter.as_ref());
+        assert!(pages.pages.is_empty());
+
+        let parsed = parse_front_matter("---\nkeywords: [deploy]\n---\n# Shipping\n");
+        pages.insert("ship.html", "Shipping", "<p>Shipping</p>", &[], parsed.front_matter.as_ref());
+        let mut index = english_index();
+        index.add_page(&pages.pages["ship.html"], "ship.html", &[]);
+        assert_eq!(index.index["deploy"], vec![[0, KEYWORD_WEIGHT]]);
+    }
+
+    #[test]
+    fn test_glossary_terms_indexed_as_synonyms() {
+        let glossary = Glossary::parse("## API\nApplication Programming Interface\n").unwrap();
+        let html = apply_glossary(&render_markdown("Call the API."), &glossary, &Default::default(), Some("GLOSSARY.html")).html;
+        let page = SearchPage {
+            title: "Calls".to_string(),
+            html,
+            headings: Vec::new(),
+            keywords: Vec::new(),
+        };
+
+        let mut index = SearchIndex::new(Tokenizer::for_language("en"), &glossary);
+        index.add_page(&page, "calls.html", &[]);
+        assert_eq!(index.index["application"], vec![[0, 1]]);
+        assert_eq!(index.index["api"], vec![[0, 1]]);
+    }
+
+    #[test]
+    fn test_search_library() {
+        let temp = TempDir::new("search-library");
+        let dir = temp.path();
+        temp.write_files(&[(
+            "library.json",
+            r#"{"books": [{"title": "API", "url": "../api/_book", "language": "en"}]}"#,
+        )]);
+
+        let config: BookConfig = serde_json::from_str(r#"{"search": {"library": "library.json"}}"#).unwrap();
+        let mut library = SearchLibrary::load(dir, &config).unwrap().unwrap();
+        library.add_own("Guide", "ja", "日本語", "ja");
+        library.write(dir).unwrap();
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("search_library.json")).unwrap()).unwrap();
+        assert_eq!(manifest["sources"][0]["url"], "ja/");
+        assert_eq!(manifest["sources"][1]["book"], "API");
+        assert_eq!(manifest["sources"][1]["url"], "../api/_book/");
+
+        assert!(SearchLibrary::load(dir, &BookConfig::default()).unwrap().is_none());
+    }
+}
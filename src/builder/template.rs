@@ -1,23 +1,68 @@
 use crate::parser::{BookConfig, FrontMatter, Summary, SummaryItem};
-use crate::builder::TocItem;
-use anyhow::Result;
+use crate::builder::plugins::PluginPage;
+use crate::builder::themes::Themes;
+use crate::builder::{LanguageLink, TocItem, UiStrings};
+use anyhow::{bail, Context as _, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
 use tera::{Context, Tera};
 
+/// Directories of a book (checked in this order) whose templates override the built-in ones
+pub const THEME_DIRS: &[&str] = &["theme", "_layouts"];
+
+/// Hook points of the page where snippets from `_includes/<hook>.html` and
+/// book.json `includes` are injected: end of `<head>`, above and below the
+/// page content, and end of `<body>`
+pub const INCLUDE_HOOKS: &[&str] = &["head", "header", "footer", "body_end"];
+
+/// Directory of a book holding hook snippets
+const INCLUDES_DIR: &str = "_includes";
+
+/// Built-in templates by name; `page.html` includes the others
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("page.html", PAGE_TEMPLATE),
+    ("sidebar.html", SIDEBAR_TEMPLATE),
+    ("header.html", HEADER_TEMPLATE),
+    ("footer.html", FOOTER_TEMPLATE),
+];
+
 pub struct Templates {
     tera: Tera,
+    /// Hooks that have a snippet, registered as `_includes/<hook>.html`
+    hooks: Vec<&'static str>,
+    themes: Themes,
 }
 
 impl Templates {
-    pub fn new(_config: &BookConfig) -> Result<Self> {
-        let mut tera = Tera::default();
+    /// Load the built-in templates, replaced by `.html` files of the same name
+    /// in the theme directory of the first of `book_dirs` that has one.
+    /// Other theme files are added too, so overrides can include them.
+    pub fn new(book_dirs: &[&Path], config: &BookConfig) -> Result<Self> {
+        let mut templates: BTreeMap<String, String> = BUILTIN_TEMPLATES
+            .iter()
+            .map(|(name, content)| (name.to_string(), content.to_string()))
+            .collect();
+
+        // Later directories are applied first so earlier ones win
+        for book_dir in book_dirs.iter().rev() {
+            for theme_dir in THEME_DIRS.iter().rev() {
+                load_theme_dir(&book_dir.join(theme_dir), &mut templates)?;
+            }
+        }
+
+        let hooks = load_include_hooks(book_dirs, config, &mut templates)?;
+        let themes = Themes::from_config(config)?;
 
-        // Register the main page template
-        tera.add_raw_template("page.html", PAGE_TEMPLATE)?;
+        let mut tera = Tera::default();
+        tera.add_raw_templates(templates)
+            .map_err(|e| anyhow::anyhow!("Invalid theme template: {}", tera_error_chain(&e)))?;
 
-        Ok(Self { tera })
+        Ok(Self { tera, hooks, themes })
     }
 
     /// Render a page with front matter metadata support
+    #[allow(clippy::too_many_arguments)]
     pub fn render_page_with_meta(
         &self,
         title: &str,
@@ -28,6 +73,8 @@ impl Templates {
         current_path: Option<&str>,
         toc_items: &[TocItem],
         front_matter: Option<&FrontMatter>,
+        languages: &[LanguageLink],
+        plugins: &PluginPage,
     ) -> Result<String> {
         let mut context = Context::new();
 
@@ -36,6 +83,18 @@ impl Templates {
         context.insert("content", content);
         context.insert("root_path", root_path);
 
+        // Document language and direction
+        context.insert("lang", config.html_lang());
+        context.insert("rtl", &config.is_rtl());
+
+        // Localized UI strings (accessible as {{ i18n.xxx }} in templates)
+        let strings = UiStrings::for_config(config);
+        context.insert("i18n", strings.as_map());
+
+        // Language switcher (multi-language books only)
+        context.insert("languages", languages);
+        context.insert("current_language", &languages.iter().find(|l| l.current));
+
         // Check plugin features
         let collapsible = config.is_plugin_enabled("collapsible-chapters");
         context.insert("collapsible", &collapsible);
@@ -65,6 +124,17 @@ impl Templates {
         let has_custom_style = config.get_website_style().is_some();
         context.insert("has_custom_style", &has_custom_style);
 
+        // Plugin template variables ({{ plugins.<name>.xxx }}) and assets
+        context.insert("plugins", &plugins.context);
+        context.insert("plugin_styles", &plugins.styles);
+        context.insert("plugin_scripts", &plugins.scripts);
+
+        // Color themes
+        context.insert("themes", &self.themes.buttons(&strings));
+        context.insert("theme_settings", &self.themes.script_settings());
+        context.insert("highlight_styles", &self.themes.highlight_styles());
+        context.insert("has_custom_themes", &self.themes.has_custom());
+
         // Add book variables to context (accessible as {{ book.xxx }} in templates)
         if !config.variables.is_empty() {
             context.insert("book", &config.variables);
@@ -84,16 +154,132 @@ impl Templates {
             context.insert("has_description", &false);
         }
 
-        let html = self.tera.render("page.html", &context)?;
+        // Hook snippets, rendered with the page context (empty when unused)
+        let mut hooks = HashMap::new();
+        for hook in INCLUDE_HOOKS {
+            let html = if self.hooks.contains(hook) {
+                let name = format!("{}/{}.html", INCLUDES_DIR, hook);
+                self.tera.render(&name, &context)
+                    .map_err(|e| anyhow::anyhow!("Failed to render {}: {}", name, tera_error_chain(&e)))?
+            } else {
+                String::new()
+            };
+            hooks.insert(*hook, html);
+        }
+        context.insert("hooks", &hooks);
+
+        let html = self.tera.render("page.html", &context)
+            .map_err(|e| anyhow::anyhow!("Failed to render page: {}", tera_error_chain(&e)))?;
         Ok(html)
     }
 }
 
+/// Add the `.html` files of a theme directory, named by their path relative to it
+fn load_theme_dir(dir: &Path, templates: &mut BTreeMap<String, String>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in walkdir::WalkDir::new(dir) {
+        let entry = entry?;
+        let path = entry.path();
+        if !entry.file_type().is_file() || path.extension().and_then(|e| e.to_str()) != Some("html") {
+            continue;
+        }
+        let name = path
+            .strip_prefix(dir)?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read template {:?}", path))?;
+        templates.insert(name, content);
+    }
+    Ok(())
+}
+
+/// Register the snippet of each hook: the book.json `includes` entry followed
+/// by `_includes/<hook>.html` of the first of `book_dirs` that has one.
+/// Returns the hooks that have a snippet.
+fn load_include_hooks(
+    book_dirs: &[&Path],
+    config: &BookConfig,
+    templates: &mut BTreeMap<String, String>,
+) -> Result<Vec<&'static str>> {
+    if let Some(unknown) = config.includes.keys().find(|key| !INCLUDE_HOOKS.contains(&key.as_str())) {
+        bail!(
+            "Unknown hook {:?} in book.json \"includes\" (expected one of: {})",
+            unknown,
+            INCLUDE_HOOKS.join(", ")
+        );
+    }
+
+    let mut hooks = Vec::new();
+    for hook in INCLUDE_HOOKS {
+        let mut parts = Vec::new();
+        if let Some(snippet) = config.includes.get(*hook) {
+            parts.push(snippet.clone());
+        }
+        let file = book_dirs
+            .iter()
+            .map(|dir| dir.join(INCLUDES_DIR).join(format!("{}.html", hook)))
+            .find(|path| path.is_file());
+        if let Some(file) = file {
+            parts.push(
+                fs::read_to_string(&file)
+                    .with_context(|| format!("Failed to read include {:?}", file))?,
+            );
+        }
+
+        if !parts.is_empty() {
+            templates.insert(format!("{}/{}.html", INCLUDES_DIR, hook), parts.join("\n"));
+            hooks.push(*hook);
+        }
+    }
+    Ok(hooks)
+}
+
+/// Tera errors keep the useful part (template name, line, cause) in their sources
+fn tera_error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Copy the built-in templates into `<book>/theme/` as a starting point for
+/// customization. Existing files are kept unless `force` is set.
+pub fn eject_theme(book_dir: &Path, force: bool) -> Result<()> {
+    let theme_dir = book_dir.join(THEME_DIRS[0]);
+    fs::create_dir_all(&theme_dir)?;
+
+    for (name, content) in BUILTIN_TEMPLATES {
+        let path = theme_dir.join(name);
+        if path.exists() && !force {
+            println!("  {}/{} already exists, skipping", THEME_DIRS[0], name);
+            continue;
+        }
+        fs::write(&path, content)?;
+        println!("  Created {}/{}", THEME_DIRS[0], name);
+    }
+
+    println!("\nEdit the templates in {:?} to customize the book layout.", theme_dir);
+    println!("Delete a file to go back to the built-in version.");
+    Ok(())
+}
+
+/// A navigable page as (html_path, title)
+type PageLink = (String, String);
+
 /// Get the previous and next pages based on the summary order
 fn get_prev_next_pages(
     items: &[SummaryItem],
     current_path: Option<&str>,
-) -> (Option<(String, String)>, Option<(String, String)>) {
+) -> (Option<PageLink>, Option<PageLink>) {
     // Flatten all pages into a list
     let pages = flatten_pages(items);
 
@@ -116,7 +302,7 @@ fn get_prev_next_pages(
 }
 
 /// Flatten summary items into a list of (html_path, title)
-fn flatten_pages(items: &[SummaryItem]) -> Vec<(String, String)> {
+fn flatten_pages(items: &[SummaryItem]) -> Vec<PageLink> {
     let mut pages = Vec::new();
 
     for item in items {
@@ -227,81 +413,53 @@ fn generate_toc_html(items: &[TocItem]) -> String {
     html
 }
 
+/// Page layout: document head, then the sidebar, header and footer templates around the content
 const PAGE_TEMPLATE: &str = r##"<!DOCTYPE html>
-<html lang="ja">
+<html lang="{{ lang }}"{% if rtl %} dir="rtl"{% endif %}>
 <head>
     <meta charset="UTF-8">
     <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>{{ title }} | {{ book_title }}</title>
+    <title>{{ title }}{% if book_title %} | {{ book_title }}{% endif %}</title>
     {% if has_description %}
     <meta name="description" content="{{ description }}">
     {% endif %}
     <link rel="stylesheet" href="{{ root_path }}gitbook/gitbook.css">
+    {% if has_custom_themes %}
+    <link rel="stylesheet" href="{{ root_path }}gitbook/themes.css">
+    {% endif %}
+    {% for style in plugin_styles %}
+    <link rel="stylesheet" href="{{ root_path }}{{ style }}">
+    {% endfor %}
     {% if has_custom_style %}
     <link rel="stylesheet" href="{{ root_path }}gitbook/style.css">
     {% endif %}
-    <link rel="stylesheet" href="https://cdnjs.cloudflare.com/ajax/libs/highlight.js/11.9.0/styles/github.min.css">
+    {% for style in highlight_styles %}
+    <link rel="stylesheet" class="hljs-theme" data-themes="{{ style.themes }}" href="{{ style.href }}">
+    {% endfor %}
     <script src="https://cdnjs.cloudflare.com/ajax/libs/highlight.js/11.9.0/highlight.min.js"></script>
     {% if mermaid %}
     <script src="https://cdn.jsdelivr.net/npm/mermaid/dist/mermaid.min.js"></script>
-    <script>mermaid.initialize({startOnLoad:true});</script>
     {% endif %}
+    <script src="{{ root_path }}gitbook/theme.js" data-themes="{{ theme_settings }}"></script>
+    {{ hooks.head | safe }}
 </head>
 <body class="book font-family-1" data-root-path="{{ root_path }}">
     <div class="book-summary">
-        <div class="search-wrapper">
-            <input type="text" class="search-input" placeholder="Search..." aria-label="Search">
-            <div class="search-results"></div>
-        </div>
-        <nav role="navigation">
-            <ul class="summary">
-                {{ sidebar | safe }}
-            </ul>
-        </nav>
+        {% include "sidebar.html" %}
     </div>
 
     <div class="book-body">
-        <div class="sidebar-toggle" title="Toggle Sidebar">
-            <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
-                <line x1="3" y1="6" x2="21" y2="6"></line>
-                <line x1="3" y1="12" x2="21" y2="12"></line>
-                <line x1="3" y1="18" x2="21" y2="18"></line>
-            </svg>
-        </div>
-        {% if fontsettings %}
-        <div class="fontsettings-toolbar" title="Font Settings">
-            <button class="fontsettings-decrease" title="Decrease font size">A-</button>
-            <button class="fontsettings-increase" title="Increase font size">A+</button>
-            <span class="fontsettings-separator"></span>
-            <button class="fontsettings-theme" data-theme="white" title="White theme"></button>
-            <button class="fontsettings-theme" data-theme="sepia" title="Sepia theme"></button>
-            <button class="fontsettings-theme" data-theme="night" title="Night theme"></button>
-        </div>
-        {% endif %}
-        {% if has_toc %}
-        <div class="toc-toggle" title="Toggle Table of Contents">
-            <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
-                <line x1="3" y1="6" x2="15" y2="6"></line>
-                <line x1="3" y1="12" x2="21" y2="12"></line>
-                <line x1="3" y1="18" x2="18" y2="18"></line>
-                <polyline points="17 4 21 6 17 8"></polyline>
-            </svg>
-        </div>
-        <nav class="page-toc">
-            <div class="toc-header">On This Page</div>
-            {{ toc | safe }}
-        </nav>
-        {% endif %}
+        {% include "header.html" %}
         <div class="body-inner">
             {% if prev_url %}
-            <a class="page-nav prev" href="{{ root_path }}{{ prev_url | safe }}" title="{{ prev_title }}">
+            <a class="page-nav prev" href="{{ root_path }}{{ prev_url | safe }}" title="{{ prev_title }}" aria-label="{{ i18n.previous_page }}: {{ prev_title }}">
                 <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
                     <polyline points="15 18 9 12 15 6"></polyline>
                 </svg>
             </a>
             {% endif %}
             {% if next_url %}
-            <a class="page-nav next" href="{{ root_path }}{{ next_url | safe }}" title="{{ next_title }}">
+            <a class="page-nav next" href="{{ root_path }}{{ next_url | safe }}" title="{{ next_title }}" aria-label="{{ i18n.next_page }}: {{ next_title }}">
                 <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
                     <polyline points="9 18 15 12 9 6"></polyline>
                 </svg>
@@ -309,30 +467,157 @@ const PAGE_TEMPLATE: &str = r##"<!DOCTYPE html>
             {% endif %}
             <div class="page-wrapper">
                 <div class="page-inner">
+                    {% if hooks.header %}<div class="page-hook-header">{{ hooks.header | safe }}</div>{% endif %}
                     <section class="markdown-section">
                         {{ content | safe }}
                     </section>
+                    {% if hooks.footer %}<div class="page-hook-footer">{{ hooks.footer | safe }}</div>{% endif %}
                 </div>
             </div>
         </div>
     </div>
 
-    {% if back_to_top %}
-    <a href="#" class="back-to-top" title="Back to top">
-        <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
-            <path d="M18 15l-6-6-6 6"/>
-        </svg>
-    </a>
-    {% endif %}
-
-    <script src="{{ root_path }}gitbook/gitbook.js"></script>
-    {% if collapsible %}
-    <script src="{{ root_path }}gitbook/collapsible.js"></script>
-    {% endif %}
-    {% if fontsettings %}
-    <script src="{{ root_path }}gitbook/fontsettings.js"></script>
-    {% endif %}
-    <script src="{{ root_path }}gitbook/search.js"></script>
+    {% include "footer.html" %}
+    {{ hooks.body_end | safe }}
 </body>
 </html>
 "##;
+
+/// Sidebar: language switcher, search box and the SUMMARY.md navigation
+const SIDEBAR_TEMPLATE: &str = r##"{% if languages %}
+<details class="lang-switcher">
+    <summary aria-label="{{ i18n.choose_language }}">{% if current_language %}{{ current_language.title }}{% else %}{{ i18n.choose_language }}{% endif %}</summary>
+    <ul class="lang-switcher-list">
+        {% for language in languages %}
+        <li{% if language.current %} class="current"{% elif not language.translated %} class="untranslated"{% endif %}>
+            <a href="{{ root_path }}{{ language.href | safe }}" lang="{{ language.lang }}" hreflang="{{ language.lang }}"{% if language.current %} aria-current="page"{% endif %}>{{ language.title }}</a>
+        </li>
+        {% endfor %}
+    </ul>
+</details>
+{% endif %}
+<div class="search-wrapper">
+    <input type="text" class="search-input" placeholder="{{ i18n.search_placeholder }}" aria-label="{{ i18n.search_label }}" data-no-results="{{ i18n.search_no_results }}">
+    <div class="search-results"></div>
+</div>
+<nav role="navigation">
+    <ul class="summary">
+        {{ sidebar | safe }}
+    </ul>
+</nav>
+"##;
+
+/// Toolbar above the page: sidebar toggle, font settings and page TOC
+const HEADER_TEMPLATE: &str = r##"<div class="sidebar-toggle" title="{{ i18n.toggle_sidebar }}">
+    <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+        <line x1="3" y1="6" x2="21" y2="6"></line>
+        <line x1="3" y1="12" x2="21" y2="12"></line>
+        <line x1="3" y1="18" x2="21" y2="18"></line>
+    </svg>
+</div>
+{% if fontsettings %}
+<div class="fontsettings-toolbar" title="{{ i18n.font_settings }}">
+    <button class="fontsettings-decrease" title="{{ i18n.font_decrease }}">A-</button>
+    <button class="fontsettings-increase" title="{{ i18n.font_increase }}">A+</button>
+    <span class="fontsettings-separator"></span>
+    {% for theme in themes %}
+    <button class="fontsettings-theme" data-theme="{{ theme.name }}" title="{{ theme.label }}"{% if theme.swatch %} style="{{ theme.swatch }}"{% endif %}></button>
+    {% endfor %}
+</div>
+{% endif %}
+{% if has_toc %}
+<div class="toc-toggle" title="{{ i18n.toggle_toc }}">
+    <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+        <line x1="3" y1="6" x2="15" y2="6"></line>
+        <line x1="3" y1="12" x2="21" y2="12"></line>
+        <line x1="3" y1="18" x2="18" y2="18"></line>
+        <polyline points="17 4 21 6 17 8"></polyline>
+    </svg>
+</div>
+<nav class="page-toc">
+    <div class="toc-header">{{ i18n.toc_header }}</div>
+    {{ toc | safe }}
+</nav>
+{% endif %}
+"##;
+
+/// End of the page body: back-to-top button and scripts
+const FOOTER_TEMPLATE: &str = r##"{% if back_to_top %}
+<a href="#" class="back-to-top" title="{{ i18n.back_to_top }}">
+    <svg xmlns="http://www.w3.org/2000/svg" width="24" height="24" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+        <path d="M18 15l-6-6-6 6"/>
+    </svg>
+</a>
+{% endif %}
+
+<script src="{{ root_path }}gitbook/gitbook.js"></script>
+{% if collapsible %}
+<script src="{{ root_path }}gitbook/collapsible.js"></script>
+{% endif %}
+{% if fontsettings %}
+<script src="{{ root_path }}gitbook/fontsettings.js"></script>
+{% endif %}
+<script src="{{ root_path }}gitbook/search.js"></script>
+{% for script in plugin_scripts %}
+<script src="{{ root_path }}{{ script }}"></script>
+{% endfor %}
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_theme_overrides() {
+        let dir = TempDir::new("theme-overrides");
+        dir.write_files(&[
+            ("_layouts/footer.html", "LAYOUTS FOOTER"),
+            ("theme/footer.html", r#"ROOT FOOTER {% include "partials/note.html" %}"#),
+            ("theme/partials/note.html", "NOTE {{ book_title }}"),
+            ("theme/header.html", "ROOT HEADER"),
+            ("ja/theme/header.html", "LANG HEADER"),
+        ]);
+        let root = dir.path();
+        let lang = root.join("ja");
+
+        let config = BookConfig {
+            title: "Guide".to_string(),
+            ..Default::default()
+        };
+        let templates = Templates::new(&[&lang, root], &config).unwrap();
+        let html = templates
+            .render_page_with_meta("Page", "<p>Body</p>", "", &config, &Summary { title: None, items: Vec::new() }, None, &[], None, &[], &PluginPage::default())
+            .unwrap();
+
+        assert!(html.contains("LANG HEADER"));
+        assert!(!html.contains("ROOT HEADER"));
+        assert!(html.contains("ROOT FOOTER NOTE Guide"));
+        assert!(!html.contains("LAYOUTS FOOTER"));
+        // Built-in sidebar is kept
+        assert!(html.contains(r#"class="search-input""#));
+    }
+
+    #[test]
+    fn test_include_hooks() {
+        let dir = TempDir::new("include-hooks");
+        dir.write_files(&[("_includes/footer.html", "<p>{{ title }} in {{ book_title }}</p>")]);
+
+        let config: BookConfig = serde_json::from_str(
+            r#"{"title": "Guide", "variables": {"id": "G-1"}, "includes": {"head": "<meta name=\"id\" content=\"{{ book.id }}\">"}}"#,
+        )
+        .unwrap();
+        let templates = Templates::new(&[dir.path()], &config).unwrap();
+        let summary = Summary { title: None, items: Vec::new() };
+        let html = templates
+            .render_page_with_meta("Page", "", "", &config, &summary, None, &[], None, &[], &PluginPage::default())
+            .unwrap();
+
+        assert!(html.contains(r#"<meta name="id" content="G-1">"#));
+        assert!(html.contains(r#"<div class="page-hook-footer"><p>Page in Guide</p></div>"#));
+        assert!(!html.contains("page-hook-header"));
+
+        let config: BookConfig = serde_json::from_str(r#"{"includes": {"sidebar": "x"}}"#).unwrap();
+        assert!(Templates::new(&[dir.path()], &config).is_err());
+    }
+}
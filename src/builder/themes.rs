@@ -0,0 +1,383 @@
+//! Color themes: the built-in white, sepia and night themes plus custom
+//! themes from book.json
+//!
+//! Colors are CSS variables (`--book-<name>`) defined in gitbook.css.
+//! Custom themes override them in `gitbook/themes.css`:
+//! ```json
+//! {
+//!     "theme": "auto",
+//!     "themes": {
+//!         "solarized": {
+//!             "label": "Solarized",
+//!             "dark": true,
+//!             "colors": { "background": "#002b36", "text": "#93a1a1" },
+//!             "highlight": "base16/solarized-dark"
+//!         }
+//!     }
+//! }
+//! ```
+
+use crate::builder::UiStrings;
+use crate::parser::BookConfig;
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::fmt::Write as _;
+
+/// Theme choice that follows the reader's system setting (white or night)
+pub const AUTO_THEME: &str = "auto";
+
+/// Colors a custom theme can set, each the `--book-<name>` variable of gitbook.css
+pub const THEME_COLORS: &[&str] = &[
+    "background",
+    "text",
+    "heading",
+    "link",
+    "accent",
+    "accent-hover",
+    "accent-text",
+    "muted",
+    "subtle",
+    "border",
+    "sidebar-background",
+    "sidebar-text",
+    "hover-background",
+    "active-background",
+    "surface",
+    "surface-hover",
+    "code-background",
+    "code-text",
+    "heading-border",
+    "table-border",
+    "table-header-background",
+    "table-stripe-background",
+    "quote-text",
+    "nav-background",
+    "nav-hover-background",
+    "mark-background",
+    "mark-text",
+    "shadow",
+    "tooltip-background",
+    "tooltip-text",
+    "glossary-hover-background",
+    "notice-background",
+    "notice-text",
+    "callout-note",
+    "callout-tip",
+    "callout-important",
+    "callout-warning",
+    "callout-caution",
+    "callout-danger",
+    "image-opacity",
+];
+
+/// Built-in themes: name, i18n key of the button label, dark
+const BUILTIN_THEMES: &[(&str, &str, bool)] = &[
+    ("white", "theme_white", false),
+    ("sepia", "theme_sepia", false),
+    ("night", "theme_night", true),
+];
+
+/// Mermaid themes a custom theme can use
+const MERMAID_THEMES: &[&str] = &["default", "dark", "forest", "neutral", "base"];
+
+/// highlight.js styles, served from the same CDN as highlight.js itself
+const HIGHLIGHT_STYLE_URL: &str = "https://cdnjs.cloudflare.com/ajax/libs/highlight.js/11.9.0/styles";
+
+struct Theme {
+    name: String,
+    /// Custom label, or the i18n key of a built-in theme
+    label: Label,
+    dark: bool,
+    highlight: String,
+    mermaid: String,
+    /// Color overrides (custom themes only)
+    colors: Vec<(String, String)>,
+}
+
+enum Label {
+    Text(String),
+    Key(&'static str),
+}
+
+/// A theme button of the font settings toolbar
+#[derive(Debug, Serialize)]
+pub struct ThemeButton {
+    pub name: String,
+    pub label: String,
+    /// Inline style setting the swatch colors of a custom theme
+    pub swatch: Option<String>,
+}
+
+/// A highlight.js stylesheet and the themes it is enabled for
+#[derive(Debug, Serialize)]
+pub struct HighlightStyle {
+    pub href: String,
+    /// Space-separated theme names
+    pub themes: String,
+}
+
+/// The themes available to a book
+pub struct Themes {
+    themes: Vec<Theme>,
+    default: String,
+}
+
+impl Themes {
+    /// Collect the built-in and custom themes of a book, checking book.json
+    /// `theme` and `themes`
+    pub fn from_config(config: &BookConfig) -> Result<Self> {
+        let mut themes: Vec<Theme> = BUILTIN_THEMES
+            .iter()
+            .map(|(name, key, dark)| Theme {
+                name: name.to_string(),
+                label: Label::Key(key),
+                dark: *dark,
+                highlight: default_highlight(*dark).to_string(),
+                mermaid: default_mermaid(*dark).to_string(),
+                colors: Vec::new(),
+            })
+            .collect();
+
+        for (name, theme) in &config.themes {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+                bail!("Invalid theme name {:?}: use lowercase letters, digits and '-'", name);
+            }
+            if name == AUTO_THEME || BUILTIN_THEMES.iter().any(|(builtin, _, _)| builtin == name) {
+                bail!("Theme {:?} conflicts with a built-in theme", name);
+            }
+
+            let mut colors = Vec::new();
+            for (key, value) in &theme.colors {
+                if !THEME_COLORS.contains(&key.as_str()) {
+                    bail!("Unknown color {:?} in theme {:?}. Available colors: {}", key, name, THEME_COLORS.join(", "));
+                }
+                if value.trim().is_empty() || value.contains([';', '{', '}', '<', '>', '"', '\\', '\n']) {
+                    bail!("Invalid value {:?} for color {:?} in theme {:?}", value, key, name);
+                }
+                colors.push((key.clone(), value.trim().to_string()));
+            }
+
+            let highlight = match &theme.highlight {
+                Some(style) => {
+                    let valid = !style.is_empty()
+                        && !style.contains("..")
+                        && style.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+                    if !valid {
+                        bail!("Invalid highlight style {:?} in theme {:?}", style, name);
+                    }
+                    style.clone()
+                }
+                None => default_highlight(theme.dark).to_string(),
+            };
+
+            let mermaid = match &theme.mermaid {
+                Some(mermaid) if !MERMAID_THEMES.contains(&mermaid.as_str()) => {
+                    bail!("Unknown Mermaid theme {:?} in theme {:?}. Available themes: {}", mermaid, name, MERMAID_THEMES.join(", "));
+                }
+                Some(mermaid) => mermaid.clone(),
+                None => default_mermaid(theme.dark).to_string(),
+            };
+
+            themes.push(Theme {
+                name: name.clone(),
+                label: Label::Text(theme.label.clone().unwrap_or_else(|| name.clone())),
+                dark: theme.dark,
+                highlight,
+                mermaid,
+                colors,
+            });
+        }
+
+        let default = config.theme.clone().unwrap_or_else(|| AUTO_THEME.to_string());
+        if default != AUTO_THEME && !themes.iter().any(|theme| theme.name == default) {
+            bail!(
+                "Unknown default theme {:?}. Use \"auto\" or one of: {}",
+                default,
+                themes.iter().map(|theme| theme.name.as_str()).collect::<Vec<_>>().join(", ")
+            );
+        }
+
+        Ok(Self { themes, default })
+    }
+
+    /// Check if the book defines custom themes (and so needs themes.css)
+    pub fn has_custom(&self) -> bool {
+        self.themes.iter().any(|theme| matches!(theme.label, Label::Text(_)))
+    }
+
+    /// Stylesheet with the color variables of the custom themes
+    pub fn custom_css(&self) -> String {
+        let mut css = String::new();
+        for theme in self.themes.iter().filter(|theme| matches!(theme.label, Label::Text(_))) {
+            let _ = writeln!(css, ":root[data-theme=\"{}\"] {{", theme.name);
+            for (key, value) in &theme.colors {
+                let _ = writeln!(css, "    --book-{}: {};", key, value);
+            }
+            css.push_str("}\n\n");
+        }
+        css
+    }
+
+    /// Theme buttons for the toolbar, followed by the "auto" button
+    pub fn buttons(&self, strings: &UiStrings) -> Vec<ThemeButton> {
+        let mut buttons: Vec<ThemeButton> = self
+            .themes
+            .iter()
+            .map(|theme| match &theme.label {
+                Label::Key(key) => ThemeButton {
+                    name: theme.name.clone(),
+                    label: strings.get(key).to_string(),
+                    swatch: None,
+                },
+                Label::Text(label) => {
+                    let color = |key: &str, default: &str| {
+                        theme.colors.iter()
+                            .find(|(name, _)| name == key)
+                            .map(|(_, value)| value.clone())
+                            .unwrap_or_else(|| default.to_string())
+                    };
+                    let (background, border) = if theme.dark { ("#1c1c1c", "#3a3a3a") } else { ("#fff", "#ccc") };
+                    ThemeButton {
+                        name: theme.name.clone(),
+                        label: label.clone(),
+                        swatch: Some(format!(
+                            "--swatch-background: {}; --swatch-border: {}",
+                            color("background", background),
+                            color("border", border)
+                        )),
+                    }
+                }
+            })
+            .collect();
+
+        buttons.push(ThemeButton {
+            name: AUTO_THEME.to_string(),
+            label: strings.get("theme_auto").to_string(),
+            swatch: None,
+        });
+        buttons
+    }
+
+    /// highlight.js stylesheets, each enabled by theme.js for its themes
+    pub fn highlight_styles(&self) -> Vec<HighlightStyle> {
+        let mut styles: Vec<(String, Vec<&str>)> = Vec::new();
+        for theme in &self.themes {
+            match styles.iter_mut().find(|(style, _)| *style == theme.highlight) {
+                Some((_, names)) => names.push(&theme.name),
+                None => styles.push((theme.highlight.clone(), vec![&theme.name])),
+            }
+        }
+        styles
+            .into_iter()
+            .map(|(style, names)| HighlightStyle {
+                href: format!("{}/{}.min.css", HIGHLIGHT_STYLE_URL, style),
+                themes: names.join(" "),
+            })
+            .collect()
+    }
+
+    /// Settings read by theme.js: the default choice and, per theme,
+    /// whether it is dark and which Mermaid theme it uses
+    pub fn script_settings(&self) -> String {
+        let themes: serde_json::Map<String, serde_json::Value> = self
+            .themes
+            .iter()
+            .map(|theme| {
+                (theme.name.clone(), serde_json::json!({ "dark": theme.dark, "mermaid": theme.mermaid }))
+            })
+            .collect();
+        serde_json::json!({ "default": self.default, "themes": themes }).to_string()
+    }
+}
+
+fn default_highlight(dark: bool) -> &'static str {
+    if dark { "github-dark" } else { "github" }
+}
+
+fn default_mermaid(dark: bool) -> &'static str {
+    if dark { "dark" } else { "default" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_from(json: &str) -> BookConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_themes() {
+        let config = config_from(r#"{"title": "Test"}"#);
+        let themes = Themes::from_config(&config).unwrap();
+        assert!(!themes.has_custom());
+        assert_eq!(themes.custom_css(), "");
+
+        let buttons = themes.buttons(&UiStrings::for_config(&config));
+        let names: Vec<&str> = buttons.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["white", "sepia", "night", "auto"]);
+        assert_eq!(buttons[2].label, "Night theme");
+
+        let styles = themes.highlight_styles();
+        assert_eq!(styles.len(), 2);
+        assert!(styles[0].href.ends_with("/github.min.css"));
+        assert_eq!(styles[0].themes, "white sepia");
+        assert_eq!(styles[1].themes, "night");
+
+        let settings: serde_json::Value = serde_json::from_str(&themes.script_settings()).unwrap();
+        assert_eq!(settings["default"], "auto");
+        assert_eq!(settings["themes"]["night"]["dark"], true);
+        assert_eq!(settings["themes"]["night"]["mermaid"], "dark");
+    }
+
+    #[test]
+    fn test_custom_theme() {
+        let config = config_from(r##"{
+            "theme": "solarized",
+            "themes": {
+                "solarized": {
+                    "label": "Solarized",
+                    "dark": true,
+                    "colors": { "background": "#002b36", "text": "#93a1a1" },
+                    "highlight": "base16/solarized-dark",
+                    "mermaid": "neutral"
+                }
+            }
+        }"##);
+        let themes = Themes::from_config(&config).unwrap();
+        assert!(themes.has_custom());
+
+        let css = themes.custom_css();
+        assert!(css.contains(":root[data-theme=\"solarized\"] {"));
+        assert!(css.contains("    --book-background: #002b36;"));
+        assert!(css.contains("    --book-text: #93a1a1;"));
+
+        let buttons = themes.buttons(&UiStrings::for_config(&config));
+        let button = buttons.iter().find(|b| b.name == "solarized").unwrap();
+        assert_eq!(button.label, "Solarized");
+        assert_eq!(button.swatch.as_deref(), Some("--swatch-background: #002b36; --swatch-border: #3a3a3a"));
+
+        let styles = themes.highlight_styles();
+        assert_eq!(styles.last().unwrap().themes, "solarized");
+        assert!(styles.last().unwrap().href.ends_with("/base16/solarized-dark.min.css"));
+
+        let settings: serde_json::Value = serde_json::from_str(&themes.script_settings()).unwrap();
+        assert_eq!(settings["default"], "solarized");
+        assert_eq!(settings["themes"]["solarized"]["mermaid"], "neutral");
+    }
+
+    #[test]
+    fn test_invalid_themes() {
+        for json in [
+            r#"{"theme": "dracula"}"#,
+            r#"{"themes": {"night": {}}}"#,
+            r#"{"themes": {"My Theme": {}}}"#,
+            r#"{"themes": {"paper": {"colors": {"backgroud": "red"}}}}"#,
+            r#"{"themes": {"paper": {"colors": {"background": "red; color: blue"}}}}"#,
+            r#"{"themes": {"paper": {"highlight": "../evil"}}}"#,
+            r#"{"themes": {"paper": {"mermaid": "pink"}}}"#,
+        ] {
+            assert!(Themes::from_config(&config_from(json)).is_err(), "{} should be rejected", json);
+        }
+    }
+}
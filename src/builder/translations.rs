@@ -0,0 +1,347 @@
+//! Cross-language page matching for multi-language books
+//!
+//! Pages in different languages are counterparts when they share the same
+//! relative path (e.g. `en/guide/setup.md` and `ja/guide/setup.md`), or when
+//! their front matter declares the same `translation_key`.
+//!
+//! A language can also name a `fallback` language in its book.json: pages it
+//! hasn't translated yet are then rendered from the fallback language's files.
+
+use crate::parser::{parse_front_matter, BookConfig, Language, Summary, SummaryItem};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A page of one language
+#[derive(Debug, Clone)]
+pub struct TranslatedPage {
+    /// Source path relative to the language directory (e.g. "guide/setup.md")
+    pub md_path: String,
+    /// Output path relative to the language directory (e.g. "guide/setup.html")
+    pub html_path: String,
+    /// Front matter `translation_key`, or the html path
+    pub key: String,
+    /// False when the source file is missing, so the page is rendered from
+    /// the fallback language
+    pub translated: bool,
+}
+
+/// Pages of one language in SUMMARY.md order, indexed both ways
+#[derive(Debug, Default)]
+struct LanguagePages {
+    pages: Vec<TranslatedPage>,
+    /// html_path → index into pages
+    by_path: HashMap<String, usize>,
+    /// translation key → index into pages
+    by_key: HashMap<String, usize>,
+}
+
+/// Page counterparts across all languages of a book
+#[derive(Debug, Default)]
+pub struct Translations {
+    /// Book root containing the language directories
+    source: PathBuf,
+    languages: Vec<Language>,
+    pages: HashMap<String, LanguagePages>,
+}
+
+/// A link to the same page in another language, for the language switcher
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageLink {
+    pub code: String,
+    pub title: String,
+    /// BCP-47 tag for the `lang` and `hreflang` attributes
+    pub lang: String,
+    /// Link relative to the current language root
+    pub href: String,
+    /// This is the language of the page being rendered
+    pub current: bool,
+    /// False when the language has no counterpart (links to its index instead)
+    pub translated: bool,
+}
+
+impl Translations {
+    /// Collect the pages of every language listed in LANGS.md.
+    /// `configs` holds the config of each language, in the same order
+    pub fn collect(source: &Path, languages: &[Language], configs: &[BookConfig]) -> Result<Self> {
+        let mut pages = HashMap::new();
+
+        for (lang, config) in languages.iter().zip(configs) {
+            let lang_source = source.join(&lang.code);
+            let structure = &config.structure;
+            let mut lang_pages = LanguagePages::default();
+
+            if lang_source.join(&structure.readme).exists() {
+                lang_pages.insert(&lang_source, &structure.readme, "index.html");
+            }
+            if lang_source.join(&structure.summary).exists() {
+                let summary = Summary::parse(&lang_source, &structure.summary)?;
+                collect_pages(&lang_source, &summary.items, &mut lang_pages);
+            }
+
+            pages.insert(lang.code.clone(), lang_pages);
+        }
+
+        Ok(Self {
+            source: source.to_path_buf(),
+            languages: languages.to_vec(),
+            pages,
+        })
+    }
+
+    /// View the translations from one language while building it
+    pub fn for_language<'a>(&'a self, lang_code: &'a str) -> LanguageView<'a> {
+        LanguageView {
+            translations: self,
+            lang_code,
+            fallback: None,
+        }
+    }
+
+    /// Languages in LANGS.md order
+    pub fn languages(&self) -> &[Language] {
+        &self.languages
+    }
+
+    /// Pages of a language in SUMMARY.md order (README first)
+    pub fn pages(&self, lang_code: &str) -> &[TranslatedPage] {
+        self.pages
+            .get(lang_code)
+            .map(|pages| pages.pages.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The page of `lang_code` with the given translation key
+    pub fn counterpart(&self, lang_code: &str, key: &str) -> Option<&TranslatedPage> {
+        let pages = self.pages.get(lang_code)?;
+        pages.by_key.get(key).map(|&idx| &pages.pages[idx])
+    }
+
+    /// Links to the counterparts of `html_path` (a page of `lang_code`) in every
+    /// language that isn't hidden
+    pub fn links_for(&self, lang_code: &str, html_path: &str) -> Vec<LanguageLink> {
+        let page = self
+            .pages
+            .get(lang_code)
+            .and_then(|pages| pages.by_path.get(html_path).map(|&idx| &pages.pages[idx]));
+        let key = page.map_or(html_path, |page| page.key.as_str());
+
+        self.languages
+            .iter()
+            .filter(|lang| !lang.hidden || lang.code == lang_code)
+            .map(|lang| {
+                let current = lang.code == lang_code;
+                let counterpart = if current { page } else { self.counterpart(&lang.code, key) };
+
+                let href = match (current, counterpart) {
+                    (true, _) => html_path.to_string(),
+                    (false, Some(page)) => format!("../{}/{}", lang.code, page.html_path),
+                    (false, None) => format!("../{}/", lang.code),
+                };
+                // Pages rendered from the fallback language aren't translated
+                let translated = match counterpart {
+                    Some(page) => page.translated,
+                    None => current,
+                };
+
+                LanguageLink {
+                    code: lang.code.clone(),
+                    title: lang.title.clone(),
+                    lang: lang.lang.clone(),
+                    href,
+                    current,
+                    translated,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Translations as seen from the language currently being built
+#[derive(Clone, Copy)]
+pub struct LanguageView<'a> {
+    translations: &'a Translations,
+    lang_code: &'a str,
+    fallback: Option<&'a Language>,
+}
+
+impl<'a> LanguageView<'a> {
+    /// Render untranslated pages from `fallback_code` (ignored if it is not
+    /// another language listed in LANGS.md)
+    pub fn with_fallback(self, fallback_code: Option<&str>) -> Self {
+        let fallback = fallback_code.and_then(|code| {
+            self.translations
+                .languages
+                .iter()
+                .find(|lang| lang.code == code && lang.code != self.lang_code)
+        });
+        Self { fallback, ..self }
+    }
+
+    /// Root directory of the multi-language book (containing LANGS.md)
+    pub fn book_root(&self) -> &'a Path {
+        &self.translations.source
+    }
+
+    /// Language switcher links for a page of this language
+    pub fn links_for(&self, html_path: &str) -> Vec<LanguageLink> {
+        self.translations.links_for(self.lang_code, html_path)
+    }
+
+    /// The fallback language's source file for a page missing from this language
+    pub fn fallback_file(&self, md_path: &str) -> Option<(PathBuf, &'a Language)> {
+        let lang = self.fallback?;
+        let file = self.translations.source.join(&lang.code).join(md_path);
+        file.exists().then_some((file, lang))
+    }
+}
+
+impl LanguagePages {
+    fn insert(&mut self, lang_source: &Path, md_path: &str, html_path: &str) {
+        let key = read_translation_key(&lang_source.join(md_path))
+            .unwrap_or_else(|| html_path.to_string());
+        self.push(TranslatedPage {
+            md_path: md_path.to_string(),
+            html_path: html_path.to_string(),
+            key,
+            translated: lang_source.join(md_path).exists(),
+        });
+    }
+
+    fn push(&mut self, page: TranslatedPage) {
+        if self.by_path.contains_key(&page.html_path) {
+            return;
+        }
+        let idx = self.pages.len();
+        self.by_path.insert(page.html_path.clone(), idx);
+        self.by_key.entry(page.key.clone()).or_insert(idx);
+        self.pages.push(page);
+    }
+}
+
+fn collect_pages(lang_source: &Path, items: &[SummaryItem], pages: &mut LanguagePages) {
+    for item in items {
+        if let SummaryItem::Link { path, children, .. } = item {
+            if let Some(md_path) = path {
+                // Remove anchor #xxx if present
+                let base_path = md_path.split('#').next().unwrap_or("");
+                if !base_path.is_empty() {
+                    let html_path = base_path.replace(".md", ".html");
+                    pages.insert(lang_source, base_path, &html_path);
+                }
+            }
+            collect_pages(lang_source, children, pages);
+        }
+    }
+}
+
+/// Read `translation_key` from a page's front matter
+fn read_translation_key(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    parse_front_matter(&content)
+        .front_matter?
+        .translation_key
+        .filter(|key| !key.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lang(code: &str, title: &str) -> Language {
+        Language::new(code, title)
+    }
+
+    fn translations(pages: &[(&str, &[(&str, &str)])]) -> Translations {
+        let mut result = Translations::default();
+        for (code, entries) in pages {
+            result.languages.push(lang(code, &code.to_uppercase()));
+            let mut lang_pages = LanguagePages::default();
+            for (path, key) in *entries {
+                lang_pages.push(TranslatedPage {
+                    md_path: path.replace(".html", ".md"),
+                    html_path: path.to_string(),
+                    key: key.to_string(),
+                    translated: true,
+                });
+            }
+            result.pages.insert(code.to_string(), lang_pages);
+        }
+        result
+    }
+
+    #[test]
+    fn test_links_by_identical_path() {
+        let t = translations(&[
+            ("en", &[("guide/setup.html", "guide/setup.html")]),
+            ("ja", &[("guide/setup.html", "guide/setup.html")]),
+        ]);
+        let links = t.links_for("en", "guide/setup.html");
+        assert_eq!(links.len(), 2);
+        assert!(links[0].current);
+        assert_eq!(links[0].href, "guide/setup.html");
+        assert!(!links[1].current);
+        assert!(links[1].translated);
+        assert_eq!(links[1].href, "../ja/guide/setup.html");
+    }
+
+    #[test]
+    fn test_links_by_translation_key() {
+        let t = translations(&[
+            ("en", &[("getting-started.html", "setup")]),
+            ("ja", &[("hajimeni.html", "setup")]),
+        ]);
+        let links = t.links_for("ja", "hajimeni.html");
+        assert_eq!(links[0].href, "../en/getting-started.html");
+        assert!(links[0].translated);
+    }
+
+    #[test]
+    fn test_fallback_page_is_untranslated() {
+        let mut t = translations(&[
+            ("en", &[("guide/setup.html", "guide/setup.html")]),
+            ("ja", &[("guide/setup.html", "guide/setup.html")]),
+        ]);
+        t.pages.get_mut("ja").unwrap().pages[0].translated = false;
+
+        let links = t.links_for("en", "guide/setup.html");
+        assert!(!links[1].translated);
+        assert_eq!(links[1].href, "../ja/guide/setup.html");
+        let links = t.links_for("ja", "guide/setup.html");
+        assert!(links[0].translated);
+        assert!(!links[1].translated);
+    }
+
+    #[test]
+    fn test_fallback_must_be_another_language() {
+        let t = translations(&[("en", &[]), ("ja", &[])]);
+        assert_eq!(t.for_language("ja").with_fallback(Some("en")).fallback.unwrap().code, "en");
+        assert!(t.for_language("ja").with_fallback(Some("ja")).fallback.is_none());
+        assert!(t.for_language("ja").with_fallback(Some("fr")).fallback.is_none());
+    }
+
+    #[test]
+    fn test_missing_counterpart_links_to_language_index() {
+        let t = translations(&[
+            ("en", &[("new-page.html", "new-page.html")]),
+            ("ja", &[]),
+        ]);
+        let links = t.links_for("en", "new-page.html");
+        assert!(!links[1].translated);
+        assert_eq!(links[1].href, "../ja/");
+    }
+
+    #[test]
+    fn test_hidden_language_is_only_linked_from_itself() {
+        let mut t = translations(&[("en", &[]), ("xx", &[])]);
+        t.languages[1].hidden = true;
+        t.languages[1].lang = "qaa".to_string();
+        let links = t.links_for("en", "index.html");
+        assert_eq!(links.len(), 1);
+        let links = t.links_for("xx", "index.html");
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[1].lang, "qaa");
+    }
+}
@@ -1,9 +1,11 @@
 mod parser;
 mod builder;
+#[cfg(test)]
+mod testing;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
 use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
 use tiny_http::{Server, Response, Header};
@@ -51,10 +53,57 @@ enum Commands {
         #[arg(short, long)]
         open: bool,
     },
+    /// Report translation coverage of a multi-language book
+    I18nStatus {
+        /// Source directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Reference language code (defaults to the first language in LANGS.md)
+        #[arg(short, long)]
+        reference: Option<String>,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check the book's configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Manage the book's theme templates
+    Theme {
+        #[command(subcommand)]
+        command: ThemeCommands,
+    },
     /// Update guidebook to the latest version
     Update,
 }
 
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Check the config files against the schema and list unsupported settings
+    Validate {
+        /// Book directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+    /// Print the JSON Schema of the config file
+    Schema,
+}
+
+#[derive(Subcommand)]
+enum ThemeCommands {
+    /// Copy the built-in templates into theme/ to customize them
+    Eject {
+        /// Book directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Overwrite templates that already exist
+        #[arg(long)]
+        force: bool,
+    },
+}
+
 fn main() -> Result<()> {
     // Check for updates in background (non-blocking)
     check_for_updates();
@@ -72,6 +121,20 @@ fn main() -> Result<()> {
         Commands::Serve { path, port, open } => {
             serve_book(&path, port, open)
         }
+        Commands::I18nStatus { path, reference, json } => {
+            builder::i18n_status(&path, reference.as_deref(), json)
+        }
+        Commands::Config { command: ConfigCommands::Validate { path } } => {
+            builder::validate_config(&path)
+        }
+        Commands::Config { command: ConfigCommands::Schema } => {
+            print!("{}", parser::schema::BOOK_SCHEMA);
+            Ok(())
+        }
+        Commands::Theme { command: ThemeCommands::Eject { path, force } } => {
+            println!("Ejecting theme templates into {:?}", path);
+            builder::eject_theme(&path, force)
+        }
         Commands::Update => {
             update_self()
         }
@@ -144,7 +207,7 @@ This file serves as your book's introduction or preface.
     Ok(())
 }
 
-fn serve_book(source: &PathBuf, port: u16, open_browser: bool) -> Result<()> {
+fn serve_book(source: &Path, port: u16, open_browser: bool) -> Result<()> {
     // Build to temp directory
     let temp_dir = std::env::temp_dir().join("guidebook-serve");
     if temp_dir.exists() {
@@ -154,10 +217,21 @@ fn serve_book(source: &PathBuf, port: u16, open_browser: bool) -> Result<()> {
     println!("Building book...");
     builder::build(source, &temp_dir)?;
 
+    // Book files may live outside the book directory (book.json `root`),
+    // and the special files may have been renamed (book.json `structure`)
+    let source = &source.canonicalize()?;
+    let config = parser::BookConfig::load(source)?;
+    let content_dir = config.content_dir(source)?;
+    let structure = config.structure;
+    let structure_files: Vec<String> = [structure.readme, structure.summary, structure.glossary, structure.langs]
+        .iter()
+        .filter_map(|name| Path::new(name).file_name()?.to_str().map(str::to_string))
+        .collect();
+
     // Version counter for hot reload
     let version = Arc::new(AtomicU64::new(1));
     let version_for_watcher = version.clone();
-    let source_for_watcher = source.clone();
+    let source_for_watcher = source.to_path_buf();
     let temp_dir_for_watcher = temp_dir.clone();
 
     // Setup file watcher
@@ -172,12 +246,17 @@ fn serve_book(source: &PathBuf, port: u16, open_browser: bool) -> Result<()> {
                 EventKind::Remove(_)
             );
             if dominated {
-                // Check if it's a relevant file (md, json, css, js)
+                // Check if it's a relevant file (md, config, css, js, or a structure file)
                 let dominated = event.paths.iter().any(|p| {
-                    p.extension()
+                    let by_extension = p.extension()
                         .and_then(|e| e.to_str())
-                        .map(|e| matches!(e, "md" | "json" | "css" | "js" | "html"))
-                        .unwrap_or(false)
+                        .map(|e| matches!(e, "md" | "json" | "yaml" | "yml" | "toml" | "css" | "js" | "html"))
+                        .unwrap_or(false);
+                    let by_name = p.file_name()
+                        .and_then(|n| n.to_str())
+                        .map(|n| structure_files.iter().any(|f| f == n))
+                        .unwrap_or(false);
+                    by_extension || by_name
                 });
                 if dominated {
                     println!("\n🔄 File changed, rebuilding...");
@@ -194,6 +273,9 @@ fn serve_book(source: &PathBuf, port: u16, open_browser: bool) -> Result<()> {
     })?;
 
     watcher.watch(source, RecursiveMode::Recursive)?;
+    if !content_dir.starts_with(source) {
+        watcher.watch(&content_dir, RecursiveMode::Recursive)?;
+    }
 
     let addr = format!("0.0.0.0:{}", port);
     let server = Server::http(&addr).map_err(|e| {
@@ -318,7 +400,7 @@ fn serve_book(source: &PathBuf, port: u16, open_browser: bool) -> Result<()> {
     Ok(())
 }
 
-fn get_content_type(path: &PathBuf) -> &'static str {
+fn get_content_type(path: &Path) -> &'static str {
     match path.extension().and_then(|e| e.to_str()) {
         Some("html") => "text/html; charset=utf-8",
         Some("css") => "text/css; charset=utf-8",
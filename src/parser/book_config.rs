@@ -1,8 +1,11 @@
-use anyhow::Result;
+use crate::parser::config_file::ConfigFile;
+use crate::parser::langs::is_rtl_language;
+use crate::parser::schema;
+use anyhow::{bail, Context, Result};
 use serde::Deserialize;
-use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 /// Plugins that are enabled by default (unless explicitly disabled with "-plugin-name")
 const DEFAULT_ENABLED_PLUGINS: &[&str] = &[
@@ -12,14 +15,34 @@ const DEFAULT_ENABLED_PLUGINS: &[&str] = &[
     "fontsettings",
 ];
 
+/// Language used for `<html lang>` when book.json doesn't set one
+const DEFAULT_LANGUAGE: &str = "en";
+
+/// Root config keys a language doesn't inherit: they describe the root
+/// language (and its text direction) or where the whole book lives
+const NOT_INHERITED_KEYS: &[&str] = &["language", "direction", "root"];
+
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct BookConfig {
     #[serde(default)]
     pub title: String,
 
+    /// Directory holding the book files, relative to book.json (HonKit `root`)
+    #[serde(default)]
+    pub root: Option<String>,
+
+    /// File names of the special book files
+    #[serde(default)]
+    pub structure: StructureConfig,
+
     #[serde(default)]
     pub plugins: Vec<String>,
 
+    /// Options of each plugin, keyed by plugin name. External plugins receive
+    /// their entry; `command` and `args` choose the executable to run
+    #[serde(default, rename = "pluginsConfig")]
+    pub plugins_config: HashMap<String, serde_json::Value>,
+
     #[serde(default)]
     pub styles: HashMap<String, String>,
 
@@ -31,31 +54,204 @@ pub struct BookConfig {
     /// This makes trailing spaces unnecessary for line breaks
     #[serde(default)]
     pub hardbreaks: bool,
+
+    /// Language code of the book (e.g. "en", "ja"), used for `<html lang>`
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// Text direction ("ltr" or "rtl"). Derived from `language` when not set
+    #[serde(default)]
+    pub direction: Option<String>,
+
+    /// UI string overrides keyed by language code, then string key
+    /// e.g. { "ja": { "search_placeholder": "キーワード" } }
+    #[serde(default)]
+    pub i18n: HashMap<String, HashMap<String, String>>,
+
+    /// Language code whose pages are shown when a page of this language
+    /// is not translated yet (multi-language books only)
+    #[serde(default)]
+    pub fallback: Option<String>,
+
+    /// Send readers of the root page of a multi-language book to the
+    /// language their browser prefers instead of showing the language list
+    #[serde(default, rename = "languageRedirect")]
+    pub language_redirect: bool,
+
+    /// Combined search across languages and other books
+    #[serde(default)]
+    pub search: SearchConfig,
+
+    /// How glossary terms are found in pages
+    #[serde(default)]
+    pub glossary: GlossaryConfig,
+
+    /// HTML snippets (Tera templates) injected at page hook points, keyed by
+    /// hook name ("head", "header", "footer", "body_end")
+    #[serde(default)]
+    pub includes: HashMap<String, String>,
+
+    /// Color theme shown until the reader picks one: "auto" (follow the
+    /// reader's system setting, the default), a built-in or a custom theme
+    #[serde(default)]
+    pub theme: Option<String>,
+
+    /// Custom color themes by name
+    #[serde(default)]
+    pub themes: BTreeMap<String, ThemeConfig>,
+}
+
+/// `search` settings in book.json
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct SearchConfig {
+    /// Let readers search every language of a multi-language book at once
+    #[serde(default)]
+    pub global: bool,
+
+    /// JSON file (relative to the book root) listing other guidebook builds
+    /// to search together with this one
+    #[serde(default)]
+    pub library: Option<String>,
+}
+
+/// `glossary` settings in book.json
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GlossaryConfig {
+    /// Match terms with the case they are written in GLOSSARY.md. A term can
+    /// override it with `{case-sensitive}` or `{case-insensitive}`
+    #[serde(rename = "caseSensitive")]
+    pub case_sensitive: bool,
+
+    /// Only mark up the first occurrence of each term in a page
+    #[serde(rename = "firstOnly")]
+    pub first_only: bool,
+
+    /// Elements whose text is never marked up, by tag name ("h2") or class
+    /// (".note"). Code and buttons are always left alone
+    pub exclude: Vec<String>,
+}
+
+impl Default for GlossaryConfig {
+    fn default() -> Self {
+        Self {
+            case_sensitive: true,
+            first_only: false,
+            exclude: Vec::new(),
+        }
+    }
+}
+
+/// `structure` settings in book.json: names of the special files,
+/// relative to the book root
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StructureConfig {
+    /// Introduction page rendered as index.html
+    pub readme: String,
+
+    /// Table of contents
+    pub summary: String,
+
+    /// Glossary terms
+    pub glossary: String,
+
+    /// Languages of a multi-language book
+    pub langs: String,
+}
+
+impl Default for StructureConfig {
+    fn default() -> Self {
+        Self {
+            readme: "README.md".to_string(),
+            summary: "SUMMARY.md".to_string(),
+            glossary: "GLOSSARY.md".to_string(),
+            langs: "LANGS.md".to_string(),
+        }
+    }
+}
+
+/// A custom color theme in book.json `themes`
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct ThemeConfig {
+    /// Tooltip of the theme button (defaults to the theme name)
+    #[serde(default)]
+    pub label: Option<String>,
+
+    /// Start from the night colors instead of the white ones
+    #[serde(default)]
+    pub dark: bool,
+
+    /// Color overrides by name, e.g. { "background": "#002b36" }
+    #[serde(default)]
+    pub colors: BTreeMap<String, String>,
+
+    /// highlight.js style for code blocks, e.g. "atom-one-dark"
+    #[serde(default)]
+    pub highlight: Option<String>,
+
+    /// Mermaid theme for diagrams, e.g. "forest"
+    #[serde(default)]
+    pub mermaid: Option<String>,
 }
 
 impl BookConfig {
+    /// Load the config of a book directory, or the defaults when it has no
+    /// config file
     pub fn load(book_dir: &Path) -> Result<Self> {
-        let config_path = book_dir.join("book.json");
-
-        if !config_path.exists() {
-            // Create default book.json with all plugins enabled
-            let default_json = r#"{
-    "title": "My Book",
-    "plugins": [
-        "collapsible-chapters",
-        "back-to-top-button",
-        "mermaid-md-adoc",
-        "fontsettings"
-    ]
-}
-"#;
-            fs::write(&config_path, default_json)?;
-            println!("  Created default book.json");
+        Ok(Self::load_with_file(book_dir)?.0)
+    }
+
+    /// Load the config together with the file it was read from. Values of
+    /// the wrong type are reported with their location in the file.
+    pub fn load_with_file(book_dir: &Path) -> Result<(Self, Option<ConfigFile>)> {
+        match ConfigFile::find(book_dir)? {
+            Some(file) => {
+                check_schema(&file)?;
+                let config = serde_json::from_value(file.value.clone())
+                    .with_context(|| format!("Invalid {}", file.path.display()))?;
+                Ok((config, Some(file)))
+            }
+            None => Ok((Self::default(), None)),
         }
+    }
 
-        let content = fs::read_to_string(&config_path)?;
-        let config: BookConfig = serde_json::from_str(&content)?;
-        Ok(config)
+    /// Load the config of a language directory merged over the book's root
+    /// config (see `merge_config`), together with the language's own file
+    pub fn load_language(lang_dir: &Path, root_file: Option<&ConfigFile>) -> Result<(Self, Option<ConfigFile>)> {
+        let mut merged = match root_file.map(|file| &file.value) {
+            Some(Value::Object(root)) => root.clone(),
+            _ => Map::new(),
+        };
+        for key in NOT_INHERITED_KEYS {
+            merged.remove(*key);
+        }
+
+        let lang_file = ConfigFile::find(lang_dir)?;
+        if let Some(file) = &lang_file {
+            check_schema(file)?;
+            if let Value::Object(overrides) = &file.value {
+                merge_config(&mut merged, overrides);
+            }
+        }
+
+        let config = serde_json::from_value(Value::Object(merged)).with_context(|| match &lang_file {
+            Some(file) => format!("Invalid {}", file.path.display()),
+            None => format!("Invalid configuration for {}", lang_dir.display()),
+        })?;
+        Ok((config, lang_file))
+    }
+
+    /// Directory holding the book files: `root` when set, otherwise the
+    /// directory of book.json
+    pub fn content_dir(&self, book_dir: &Path) -> Result<PathBuf> {
+        match self.root.as_deref().filter(|root| !root.is_empty()) {
+            Some(root) => book_dir
+                .join(root)
+                .canonicalize()
+                .with_context(|| format!("Book root {:?} (book.json `root`) not found", root)),
+            None => Ok(book_dir.to_path_buf()),
+        }
     }
 
     /// Check if a plugin is enabled.
@@ -76,15 +272,107 @@ impl BookConfig {
         DEFAULT_ENABLED_PLUGINS.contains(&name)
     }
 
+    /// Check if a plugin name is one of the built-in plugins
+    pub fn is_builtin_plugin(name: &str) -> bool {
+        DEFAULT_ENABLED_PLUGINS.contains(&name)
+    }
+
+    /// Plugins listed in `plugins` that are not built in, in order
+    pub fn external_plugins(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for name in &self.plugins {
+            let name = name.as_str();
+            if name.is_empty() || name.starts_with('-') || DEFAULT_ENABLED_PLUGINS.contains(&name) || names.contains(&name) {
+                continue;
+            }
+            names.push(name);
+        }
+        names
+    }
+
     /// Get custom CSS path for website
     pub fn get_website_style(&self) -> Option<&String> {
         self.styles.get("website")
     }
+
+    /// Language code for the `<html lang>` attribute
+    pub fn html_lang(&self) -> &str {
+        self.language
+            .as_deref()
+            .filter(|lang| !lang.is_empty())
+            .unwrap_or(DEFAULT_LANGUAGE)
+    }
+
+    /// Check if pages should be laid out right-to-left.
+    /// An explicit `direction` wins, otherwise it follows the language.
+    pub fn is_rtl(&self) -> bool {
+        match self.direction.as_deref() {
+            Some(direction) => direction.eq_ignore_ascii_case("rtl"),
+            None => is_rtl_language(self.html_lang()),
+        }
+    }
+}
+
+/// Fail with the located schema errors of a config file
+fn check_schema(file: &ConfigFile) -> Result<()> {
+    let mut errors = schema::validate(&file.value);
+    if errors.is_empty() {
+        return Ok(());
+    }
+    errors.sort_by_key(|error| file.locate(&error.key));
+    let lines: Vec<String> = errors
+        .iter()
+        .map(|error| format!("  {}: {}", file.location(&error.key), error))
+        .collect();
+    bail!("Invalid configuration in {}:\n{}", file.path.display(), lines.join("\n"));
+}
+
+/// Merge a language config over the root config. `plugins` are combined
+/// (an entry replaces the root's entry for the same plugin, so "-name"
+/// disables a root plugin), objects such as `variables` and `styles` are
+/// merged key by key, and any other value replaces the root's.
+pub fn merge_config(base: &mut Map<String, Value>, overrides: &Map<String, Value>) {
+    for (key, value) in overrides {
+        match (key.as_str(), base.get_mut(key), value) {
+            ("plugins", Some(Value::Array(plugins)), Value::Array(lang_plugins)) => {
+                for plugin in lang_plugins {
+                    let name = plugin.as_str().unwrap_or_default();
+                    let name = name.strip_prefix('-').unwrap_or(name);
+                    plugins.retain(|p| {
+                        let existing = p.as_str().unwrap_or_default();
+                        existing.strip_prefix('-').unwrap_or(existing) != name
+                    });
+                    plugins.push(plugin.clone());
+                }
+            }
+            (_, Some(existing), _) => merge_value(existing, value),
+            (_, None, _) => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn merge_value(base: &mut Value, value: &Value) {
+    match (base, value) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, value) => *base = value.clone(),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::TempDir;
 
     #[test]
     fn test_parse_book_json() {
@@ -143,6 +431,88 @@ mod tests {
         assert!(config.is_plugin_enabled("collapsible-chapters"));
     }
 
+    #[test]
+    fn test_external_plugins() {
+        let json = r#"{
+            "plugins": ["fontsettings", "-sharing", "word-count", "word-count", "mathjax"],
+            "pluginsConfig": { "word-count": { "command": "./wc-plugin" } }
+        }"#;
+        let config: BookConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.external_plugins(), ["word-count", "mathjax"]);
+        assert_eq!(config.plugins_config["word-count"]["command"], "./wc-plugin");
+    }
+
+    #[test]
+    fn test_structure() {
+        // Unset names keep their defaults
+        let json = r#"{"root": "./docs", "structure": {"readme": "intro.md", "summary": "toc.md"}}"#;
+        let config: BookConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.root.as_deref(), Some("./docs"));
+        assert_eq!(config.structure.readme, "intro.md");
+        assert_eq!(config.structure.summary, "toc.md");
+        assert_eq!(config.structure.glossary, "GLOSSARY.md");
+        assert_eq!(config.structure.langs, "LANGS.md");
+
+        let config = BookConfig::default();
+        assert_eq!(config.structure.readme, "README.md");
+        assert_eq!(config.content_dir(Path::new("/book")).unwrap(), Path::new("/book"));
+    }
+
+    #[test]
+    fn test_merge_language_config() {
+        let mut root = serde_json::json!({
+            "title": "Book",
+            "plugins": ["back-to-top-button", "word-count", "-mermaid-md-adoc"],
+            "variables": { "version": "1.0", "links": { "site": "https://example.com", "repo": "https://git.example.com" } },
+            "styles": { "website": "styles/website.css", "print": "styles/print.css" },
+            "hardbreaks": true
+        });
+        let lang = serde_json::json!({
+            "title": "本",
+            "plugins": ["-word-count", "mermaid-md-adoc", "fontsettings"],
+            "variables": { "links": { "site": "https://example.jp" }, "support": "サポート" },
+            "styles": { "website": "styles/ja.css" }
+        });
+        merge_config(root.as_object_mut().unwrap(), lang.as_object().unwrap());
+
+        assert_eq!(root, serde_json::json!({
+            "title": "本",
+            "plugins": ["back-to-top-button", "-word-count", "mermaid-md-adoc", "fontsettings"],
+            "variables": {
+                "version": "1.0",
+                "links": { "site": "https://example.jp", "repo": "https://git.example.com" },
+                "support": "サポート"
+            },
+            "styles": { "website": "styles/ja.css", "print": "styles/print.css" },
+            "hardbreaks": true
+        }));
+
+        let config: BookConfig = serde_json::from_value(root).unwrap();
+        assert!(!config.is_plugin_enabled("word-count"));
+        assert!(config.is_plugin_enabled("mermaid-md-adoc"));
+    }
+
+    #[test]
+    fn test_language_config_not_inherited() {
+        let content = r#"{"title": "Book", "language": "he", "direction": "rtl", "root": "src"}"#;
+        let root_file = ConfigFile {
+            path: PathBuf::from("book.json"),
+            content: content.to_string(),
+            value: serde_json::from_str(content).unwrap(),
+            ignored: Vec::new(),
+        };
+        let dir = TempDir::new("language-config");
+        let (config, lang_file) = BookConfig::load_language(dir.path(), Some(&root_file)).unwrap();
+
+        assert!(lang_file.is_none());
+        assert_eq!(config.title, "Book");
+        assert_eq!(config.language, None);
+        assert_eq!(config.direction, None);
+        assert_eq!(config.root, None);
+        assert!(!config.is_rtl());
+    }
+
     #[test]
     fn test_parse_variables() {
         let json = r#"{
@@ -160,6 +530,32 @@ mod tests {
         assert_eq!(config.variables.get("year").unwrap(), 2024);
     }
 
+    #[test]
+    fn test_html_lang() {
+        let config: BookConfig = serde_json::from_str(r#"{"language": "ja"}"#).unwrap();
+        assert_eq!(config.html_lang(), "ja");
+        assert!(!config.is_rtl());
+
+        // Missing or empty language falls back to the default
+        let config: BookConfig = serde_json::from_str(r#"{"title": "Test"}"#).unwrap();
+        assert_eq!(config.html_lang(), "en");
+        let config: BookConfig = serde_json::from_str(r#"{"language": ""}"#).unwrap();
+        assert_eq!(config.html_lang(), "en");
+    }
+
+    #[test]
+    fn test_text_direction() {
+        // Direction follows the language by default
+        let config: BookConfig = serde_json::from_str(r#"{"language": "ar"}"#).unwrap();
+        assert!(config.is_rtl());
+
+        // Explicit direction overrides the language
+        let config: BookConfig = serde_json::from_str(r#"{"language": "ar", "direction": "ltr"}"#).unwrap();
+        assert!(!config.is_rtl());
+        let config: BookConfig = serde_json::from_str(r#"{"language": "en", "direction": "rtl"}"#).unwrap();
+        assert!(config.is_rtl());
+    }
+
     #[test]
     fn test_empty_variables() {
         let json = r#"{"title": "Test"}"#;
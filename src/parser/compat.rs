@@ -0,0 +1,306 @@
+//! Compatibility report for book.json
+//!
+//! guidebook reads book.json with serde defaults, so keys it doesn't know are
+//! dropped without a word. This lists them (and plugins that can't run) with
+//! the guidebook equivalent where there is one, so a book migrated from
+//! HonKit/GitBook doesn't silently lose features.
+
+use crate::parser::schema::{property_names, SCHEMA};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::LazyLock;
+
+/// Top-level keys guidebook reads: the properties of the JSON Schema.
+/// `description` and `author` are accepted as book metadata.
+static KNOWN_KEYS: LazyLock<Vec<&str>> = LazyLock::new(|| property_names(&SCHEMA));
+
+/// Keys of nested objects guidebook reads: (parent key, keys), for the
+/// properties the schema describes as objects with `properties`
+static KNOWN_NESTED_KEYS: LazyLock<Vec<(&str, Vec<&str>)>> = LazyLock::new(|| {
+    SCHEMA["properties"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, property)| property.get("properties").is_some())
+        .map(|(parent, property)| (parent.as_str(), property_names(property)))
+        .collect()
+});
+
+/// HonKit/GitBook keys guidebook doesn't support, with what to do instead
+const HONKIT_KEYS: &[(&str, &str)] = &[
+    ("gitbook", "the GitBook version constraint isn't needed, remove it"),
+    ("honkit", "the HonKit version constraint isn't needed, remove it"),
+    ("links", "sidebar links aren't supported; add them to SUMMARY.md or to _includes/header.html (see Theme Templates)"),
+    ("pdf", "PDF output isn't supported; readers can print pages from the browser"),
+    ("isbn", "not used by guidebook"),
+];
+
+/// `styles` entries for other HonKit output formats
+const HONKIT_STYLES: &[&str] = &["pdf", "epub", "mobi", "ebook", "print"];
+
+/// Plugins built into HonKit/GitBook or common community plugins, with the
+/// guidebook equivalent
+const HONKIT_PLUGINS: &[(&str, &str)] = &[
+    ("search", "search is built in, remove it from plugins"),
+    ("lunr", "search is built in, remove it from plugins"),
+    ("search-plus", "search is built in (including Chinese and Japanese text), remove it from plugins"),
+    ("search-pro", "search is built in (including Chinese and Japanese text), remove it from plugins"),
+    ("highlight", "code highlighting is built in, remove it from plugins"),
+    ("sharing", "social sharing buttons aren't supported; use _includes/footer.html (see Theme Templates)"),
+    ("theme-default", "the default theme is built in; customize it with theme/ templates (see Theme Templates)"),
+    ("livereload", "`guidebook serve` reloads pages when files change, remove it from plugins"),
+    ("expandable-chapters", "use the built-in collapsible-chapters plugin"),
+    ("expandable-chapters-small", "use the built-in collapsible-chapters plugin"),
+    ("chapter-fold", "use the built-in collapsible-chapters plugin"),
+    ("back-to-top", "use the built-in back-to-top-button plugin"),
+    ("mermaid", "use the built-in mermaid-md-adoc plugin"),
+    ("mermaid-gb3", "use the built-in mermaid-md-adoc plugin"),
+    ("mermaid-2", "use the built-in mermaid-md-adoc plugin"),
+    ("page-toc", "every page has a built-in table of contents"),
+    ("page-treeview", "every page has a built-in table of contents"),
+    ("anchor-navigation-ex", "headings get anchors and every page has a built-in table of contents"),
+    ("anchors", "headings get anchors automatically"),
+    ("ga", "add the analytics snippet to _includes/head.html or includes.head (see Theme Templates)"),
+    ("gtag", "add the analytics snippet to _includes/head.html or includes.head (see Theme Templates)"),
+    ("google-analytics", "add the analytics snippet to _includes/head.html or includes.head (see Theme Templates)"),
+    ("insert-logo", "add the logo to _includes/header.html or a theme/ template (see Theme Templates)"),
+    ("tbfed-pagefooter", "add the footer to _includes/footer.html or includes.footer (see Theme Templates)"),
+    ("page-footer-ex", "add the footer to _includes/footer.html or includes.footer (see Theme Templates)"),
+    ("glossary", "GLOSSARY.md is supported natively, remove it from plugins"),
+];
+
+/// `pluginsConfig` entries of built-in plugins that map to guidebook keys
+const HONKIT_PLUGIN_OPTIONS: &[(&str, &str, &str)] = &[
+    ("fontsettings", "theme", "set the default color theme with \"theme\" in book.json (see Color Themes)"),
+];
+
+/// One thing in book.json that guidebook doesn't understand
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// Path of the key the finding is about, for locating it in the file
+    pub key: Vec<String>,
+    /// What the finding is about, e.g. "key `pdf`" or "plugin `lunr`"
+    pub subject: String,
+    /// What happens to it or what to use instead
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.subject, self.message)
+    }
+}
+
+/// Check a book.json document. `is_plugin_available` tells whether an
+/// external plugin can be started; `is_builtin_plugin` whether a name is built in.
+pub fn check_config(
+    raw: &Value,
+    is_builtin_plugin: impl Fn(&str) -> bool,
+    is_plugin_available: impl Fn(&str) -> bool,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let Some(object) = raw.as_object() else {
+        return findings;
+    };
+
+    for (key, value) in object {
+        if KNOWN_KEYS.contains(&key.as_str()) {
+            check_nested(key, value, &mut findings);
+            continue;
+        }
+        let message = match HONKIT_KEYS.iter().find(|(name, _)| name == key) {
+            Some((_, message)) => message.to_string(),
+            None => unknown_message(key, &KNOWN_KEYS),
+        };
+        findings.push(Finding { key: vec![key.clone()], subject: format!("key `{}`", key), message });
+    }
+
+    let plugins: Vec<&str> = object
+        .get("plugins")
+        .and_then(Value::as_array)
+        .map(|plugins| plugins.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    for name in &plugins {
+        if name.starts_with('-') || is_builtin_plugin(name) || is_plugin_available(name) {
+            continue;
+        }
+        let message = match HONKIT_PLUGINS.iter().find(|(plugin, _)| plugin == name) {
+            Some((_, message)) => message.to_string(),
+            None => format!(
+                "no such plugin; set pluginsConfig.{}.command, or add plugins/{} or guidebook-plugin-{} on the PATH (see Plugins)",
+                name, name, name
+            ),
+        };
+        findings.push(Finding { key: vec!["plugins".to_string()], subject: format!("plugin `{}`", name), message });
+    }
+
+    if let Some(config) = object.get("pluginsConfig").and_then(Value::as_object) {
+        check_plugins_config(config, &plugins, &is_builtin_plugin, &mut findings);
+    }
+
+    findings
+}
+
+/// Check the keys of nested objects guidebook reads
+fn check_nested(key: &str, value: &Value, findings: &mut Vec<Finding>) {
+    let Some((_, known)) = KNOWN_NESTED_KEYS.iter().find(|(parent, _)| *parent == key) else {
+        return;
+    };
+    let Some(object) = value.as_object() else {
+        return;
+    };
+    for nested in object.keys() {
+        if known.contains(&nested.as_str()) {
+            continue;
+        }
+        let message = if key == "styles" && HONKIT_STYLES.contains(&nested.as_str()) {
+            "only styles.website is used".to_string()
+        } else {
+            unknown_message(nested, known)
+        };
+        findings.push(Finding {
+            key: vec![key.to_string(), nested.clone()],
+            subject: format!("key `{}.{}`", key, nested),
+            message,
+        });
+    }
+}
+
+/// Check `pluginsConfig` entries: options of built-in HonKit plugins that
+/// guidebook has a key for, and entries for plugins that aren't enabled
+fn check_plugins_config(
+    config: &Map<String, Value>,
+    plugins: &[&str],
+    is_builtin_plugin: &impl Fn(&str) -> bool,
+    findings: &mut Vec<Finding>,
+) {
+    for (name, options) in config {
+        for (plugin, option, message) in HONKIT_PLUGIN_OPTIONS {
+            if name == plugin && options.get(option).is_some() {
+                findings.push(Finding {
+                    key: vec!["pluginsConfig".to_string(), name.clone(), option.to_string()],
+                    subject: format!("key `pluginsConfig.{}.{}`", plugin, option),
+                    message: message.to_string(),
+                });
+            }
+        }
+        if !is_builtin_plugin(name) && !plugins.contains(&name.as_str()) {
+            findings.push(Finding {
+                key: vec!["pluginsConfig".to_string(), name.clone()],
+                subject: format!("key `pluginsConfig.{}`", name),
+                message: format!("plugin `{}` isn't in plugins, so this has no effect", name),
+            });
+        }
+    }
+}
+
+/// Message for an unknown key, suggesting a known key with a similar spelling
+fn unknown_message(key: &str, known: &[&str]) -> String {
+    match suggest(key, known) {
+        Some(suggestion) => format!("unknown key, ignored. Did you mean `{}`?", suggestion),
+        None => "unknown key, ignored".to_string(),
+    }
+}
+
+/// The known name closest to `name`, if it is close enough to be a typo
+pub fn suggest<'a>(name: &str, known: &[&'a str]) -> Option<&'a str> {
+    let lower = name.to_lowercase();
+    known
+        .iter()
+        .map(|candidate| (edit_distance(&lower, &candidate.to_lowercase()), *candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.chars().count() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Edit distance between two strings, counting a swap of neighbouring
+/// characters as one edit (optimal string alignment)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(json: &str) -> Vec<String> {
+        let raw: Value = serde_json::from_str(json).unwrap();
+        check_config(&raw, |name| name == "fontsettings", |name| name == "word-count")
+            .iter()
+            .map(|finding| finding.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_known_config_has_no_findings() {
+        let findings = check(r#"{
+            "title": "Book",
+            "plugins": ["fontsettings", "-sharing", "word-count"],
+            "pluginsConfig": { "word-count": { "wpm": 200 } },
+            "styles": { "website": "styles/website.css" },
+            "search": { "global": true }
+        }"#);
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+
+    #[test]
+    fn test_honkit_keys_and_typos() {
+        let findings = check(r#"{
+            "gitbook": "3.x",
+            "pdf": { "paperSize": "a4" },
+            "titel": "Book",
+            "xyz": 1,
+            "styles": { "pdf": "styles/pdf.css", "webiste": "a.css" },
+            "search": { "global": true, "libary": "books.json" }
+        }"#);
+        assert!(findings.contains(&"key `gitbook`: the GitBook version constraint isn't needed, remove it".to_string()));
+        assert!(findings.iter().any(|f| f.starts_with("key `pdf`: PDF output isn't supported")));
+        assert!(findings.contains(&"key `titel`: unknown key, ignored. Did you mean `title`?".to_string()));
+        assert!(findings.contains(&"key `xyz`: unknown key, ignored".to_string()));
+        assert!(findings.contains(&"key `styles.pdf`: only styles.website is used".to_string()));
+        assert!(findings.contains(&"key `styles.webiste`: unknown key, ignored. Did you mean `website`?".to_string()));
+        assert!(findings.contains(&"key `search.libary`: unknown key, ignored. Did you mean `library`?".to_string()));
+        assert_eq!(findings.len(), 7);
+    }
+
+    #[test]
+    fn test_plugins() {
+        let findings = check(r#"{
+            "plugins": ["lunr", "my-plugin", "-search"],
+            "pluginsConfig": {
+                "fontsettings": { "theme": "night" },
+                "sharing": { "facebook": false }
+            }
+        }"#);
+        assert_eq!(findings.len(), 4, "{:?}", findings);
+        assert!(findings[0].starts_with("plugin `lunr`: search is built in"));
+        assert!(findings[1].starts_with("plugin `my-plugin`: no such plugin; set pluginsConfig.my-plugin.command"));
+        assert!(findings[2].starts_with("key `pluginsConfig.fontsettings.theme`: set the default color theme"));
+        assert!(findings[3].starts_with("key `pluginsConfig.sharing`: plugin `sharing` isn't in plugins"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(suggest("lanugage", &KNOWN_KEYS), Some("language"));
+        assert_eq!(suggest("Plugins", &KNOWN_KEYS), Some("plugins"));
+        assert_eq!(suggest("foo", &KNOWN_KEYS), None);
+    }
+}
@@ -0,0 +1,178 @@
+//! Book configuration file discovery
+//!
+//! A book is configured by the first of CONFIG_FILES found in its directory.
+//! Every format is read into the same JSON value, so the rest of guidebook
+//! doesn't care which one the book uses.
+
+use crate::parser::js_config;
+use anyhow::{anyhow, bail, Context, Result};
+use regex::Regex;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Config file names in order of precedence
+pub const CONFIG_FILES: &[&str] = &["book.json", "book.yaml", "book.yml", "book.toml", "book.js"];
+
+/// A book's configuration file
+#[derive(Debug, Clone)]
+pub struct ConfigFile {
+    pub path: PathBuf,
+    /// Text of the file
+    pub content: String,
+    /// Contents as JSON, whatever the file format
+    pub value: Value,
+    /// Config files of lower precedence in the same directory, which are not read
+    pub ignored: Vec<PathBuf>,
+}
+
+impl ConfigFile {
+    /// Find and read the config file of a book directory (None when it has none)
+    pub fn find(book_dir: &Path) -> Result<Option<Self>> {
+        let mut existing = CONFIG_FILES
+            .iter()
+            .map(|name| book_dir.join(name))
+            .filter(|path| path.is_file());
+        let Some(path) = existing.next() else {
+            return Ok(None);
+        };
+        let ignored = existing.collect();
+        let content = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let value = parse(&path, &content)?;
+        Ok(Some(Self { path, content, value, ignored }))
+    }
+
+    /// File name, e.g. "book.yaml"
+    pub fn file_name(&self) -> &str {
+        self.path.file_name().and_then(|name| name.to_str()).unwrap_or("book.json")
+    }
+
+    /// "file:line:column" of a key, or just the file when the key isn't found
+    pub fn location(&self, key: &[String]) -> String {
+        match self.locate(key) {
+            Some((line, column)) => format!("{}:{}:{}", self.path.display(), line, column),
+            None => self.path.display().to_string(),
+        }
+    }
+
+    /// Line and column of a key path. Each key is looked for after the one
+    /// before it, which works the same for every format; array indexes point
+    /// at the array's key.
+    pub fn locate(&self, key: &[String]) -> Option<(usize, usize)> {
+        let mut offset = 0;
+        let mut found = None;
+        for part in key.iter().filter(|part| !part.chars().all(|c| c.is_ascii_digit())) {
+            let pattern = format!(r#"(?m)(?:^|[\s{{,\[.])(["']?{}["']?)\s*[:=\]]"#, regex::escape(part));
+            let re = Regex::new(&pattern).ok()?;
+            let start = re.captures(&self.content[offset..])?.get(1)?.start() + offset;
+            found = Some(start);
+            offset = start + part.len();
+        }
+        let start = found?;
+        let before = &self.content[..start];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        Some((line, column))
+    }
+}
+
+/// Parse a config file into JSON. Syntax errors start with "file:line:column"
+fn parse(path: &Path, content: &str) -> Result<Value> {
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let value = match extension {
+        "yaml" | "yml" => serde_yaml::from_str(content).map_err(|e| match e.location() {
+            Some(location) => syntax_error(path, location.line(), location.column(), &e.to_string()),
+            None => anyhow!("{}: {}", path.display(), e),
+        })?,
+        "toml" => content.parse::<toml::Table>().map(toml_to_json).map_err(|e| match e.span() {
+            Some(span) => {
+                let before = &content[..span.start];
+                let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+                syntax_error(path, before.matches('\n').count() + 1, column, e.message())
+            }
+            None => anyhow!("{}: {}", path.display(), e.message()),
+        })?,
+        "js" => js_config::parse(content).map_err(|e| anyhow!("{}:{}", path.display(), e))?,
+        _ => serde_json::from_str(content).map_err(|e| syntax_error(path, e.line(), e.column(), &e.to_string()))?,
+    };
+
+    match value {
+        Value::Object(_) => Ok(value),
+        // An empty YAML file
+        Value::Null => Ok(Value::Object(Default::default())),
+        _ => bail!("{} must contain an object of settings", path.display()),
+    }
+}
+
+/// A TOML table as JSON; dates and times are kept as strings
+fn toml_to_json(table: toml::Table) -> Value {
+    fn convert(value: toml::Value) -> Value {
+        match value {
+            toml::Value::String(s) => Value::String(s),
+            toml::Value::Integer(n) => Value::from(n),
+            toml::Value::Float(n) => Value::from(n),
+            toml::Value::Boolean(b) => Value::Bool(b),
+            toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+            toml::Value::Array(items) => Value::Array(items.into_iter().map(convert).collect()),
+            toml::Value::Table(table) => toml_to_json(table),
+        }
+    }
+    Value::Object(table.into_iter().map(|(key, value)| (key, convert(value))).collect())
+}
+
+/// A serde error with its position moved to the front
+fn syntax_error(path: &Path, line: usize, column: usize, message: &str) -> anyhow::Error {
+    let message = message.split(" at line ").next().unwrap_or(message);
+    anyhow!("{}:{}:{}: {}", path.display(), line, column, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_config_file_precedence() {
+        let temp = TempDir::new("config-file");
+        let dir = temp.path();
+
+        assert!(ConfigFile::find(dir).unwrap().is_none());
+
+        fs::write(dir.join("book.toml"), "title = \"From TOML\"\n\n[variables]\nreleased = 2024-01-15\n").unwrap();
+        let file = ConfigFile::find(dir).unwrap().unwrap();
+        assert_eq!(file.file_name(), "book.toml");
+        assert_eq!(file.value["title"], "From TOML");
+        assert_eq!(file.value["variables"]["released"], "2024-01-15");
+
+        fs::write(dir.join("book.yaml"), "title: From YAML\nplugins:\n  - back-to-top-button\n").unwrap();
+        let file = ConfigFile::find(dir).unwrap().unwrap();
+        assert_eq!(file.file_name(), "book.yaml");
+        assert_eq!(file.value["plugins"][0], "back-to-top-button");
+        assert_eq!(file.ignored, [dir.join("book.toml")]);
+
+        fs::write(dir.join("book.json"), "[]").unwrap();
+        let err = ConfigFile::find(dir).unwrap_err().to_string();
+        assert!(err.contains("must contain an object"), "{}", err);
+    }
+
+    #[test]
+    fn test_locate_keys() {
+        let content = "{\n    \"title\": \"Book\",\n    \"search\": {\n        \"title\": 1,\n        \"global\": \"yes\"\n    }\n}\n";
+        let file = ConfigFile {
+            path: PathBuf::from("book.json"),
+            content: content.to_string(),
+            value: serde_json::from_str(content).unwrap(),
+            ignored: Vec::new(),
+        };
+        let key = |parts: &[&str]| parts.iter().map(|part| part.to_string()).collect::<Vec<_>>();
+        assert_eq!(file.locate(&key(&["title"])), Some((2, 5)));
+        assert_eq!(file.location(&key(&["search", "global"])), "book.json:5:9");
+        assert_eq!(file.location(&key(&["missing"])), "book.json");
+
+        let err = parse(Path::new("book.json"), "{\n  \"title\": ,\n}").unwrap_err().to_string();
+        assert_eq!(err, "book.json:2:12: expected value");
+
+        let err = parse(Path::new("book.toml"), "title = \"Book\"\nplugins = [\n").unwrap_err().to_string();
+        assert!(err.starts_with("book.toml:3:1: "), "{}", err);
+    }
+}
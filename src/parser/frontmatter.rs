@@ -10,10 +10,10 @@
 //! # Content
 //! ```
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Front matter metadata extracted from markdown files
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct FrontMatter {
     /// Custom page title (overrides default from SUMMARY.md)
     #[serde(default)]
@@ -23,6 +23,23 @@ pub struct FrontMatter {
     #[serde(default)]
     pub description: Option<String>,
 
+    /// Key matching this page to its translations in a multi-language book
+    /// (defaults to the page's relative path)
+    #[serde(default)]
+    pub translation_key: Option<String>,
+
+    /// Set to false to leave the page out of the search index
+    #[serde(default)]
+    pub search: Option<bool>,
+
+    /// Extra search terms, ranked like words in the page title
+    #[serde(default)]
+    pub keywords: Vec<String>,
+
+    /// Set to false to leave glossary terms of the page unmarked
+    #[serde(default)]
+    pub glossary: Option<bool>,
+
     /// Additional custom fields (for extensibility)
     #[serde(flatten)]
     #[allow(dead_code)]
@@ -85,10 +102,10 @@ pub fn parse_front_matter(content: &str) -> ParsedContent {
 
     // Skip any whitespace/newline after opening ---
     let after_opening = after_opening.trim_start_matches([' ', '\t']);
-    let after_opening = if after_opening.starts_with('\n') {
-        &after_opening[1..]
-    } else if after_opening.starts_with("\r\n") {
-        &after_opening[2..]
+    let after_opening = if let Some(rest) = after_opening.strip_prefix('\n') {
+        rest
+    } else if let Some(rest) = after_opening.strip_prefix("\r\n") {
+        rest
     } else if after_opening.is_empty() {
         after_opening
     } else {
@@ -101,10 +118,10 @@ pub fn parse_front_matter(content: &str) -> ParsedContent {
 
     // Find the closing ---
     // First, check if the content starts with --- (empty front matter case)
-    let (yaml_content, remaining) = if after_opening.starts_with("---\n") {
-        ("", &after_opening[4..])
-    } else if after_opening.starts_with("---\r\n") {
-        ("", &after_opening[5..])
+    let (yaml_content, remaining) = if let Some(rest) = after_opening.strip_prefix("---\n") {
+        ("", rest)
+    } else if let Some(rest) = after_opening.strip_prefix("---\r\n") {
+        ("", rest)
     } else if after_opening == "---" {
         ("", "")
     } else {
@@ -241,6 +258,20 @@ Content
         assert!(fm.extra.contains_key("custom_field"));
     }
 
+    #[test]
+    fn test_parse_translation_key() {
+        let content = r#"---
+translation_key: getting-started
+---
+
+Content
+"#;
+        let parsed = parse_front_matter(content);
+        let fm = parsed.front_matter.unwrap();
+        assert_eq!(fm.translation_key.as_deref(), Some("getting-started"));
+        assert!(!fm.extra.contains_key("translation_key"));
+    }
+
     #[test]
     fn test_parse_invalid_yaml() {
         let content = r#"---
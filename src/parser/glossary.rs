@@ -1,21 +1,44 @@
+use crate::parser::book_config::GlossaryConfig;
 use anyhow::Result;
-use std::collections::HashMap;
+use pulldown_cmark::{Event, Parser, TagEnd};
+use regex::Regex;
 use std::fs;
 use std::path::Path;
+use std::sync::LazyLock;
 
 /// Glossary containing all terms and their definitions
 #[derive(Debug, Clone, Default)]
 pub struct Glossary {
-    /// Map from term to definition
-    pub entries: HashMap<String, String>,
-    /// Terms sorted by length (longest first) for replacement
-    pub sorted_terms: Vec<String>,
+    /// Title from the # heading of GLOSSARY.md
+    pub title: Option<String>,
+    /// Terms in GLOSSARY.md order
+    pub terms: Vec<GlossaryTerm>,
+    /// Names and aliases of every term, longest first to avoid partial
+    /// replacements, with the index of their term
+    pub sorted_terms: Vec<(String, usize)>,
+}
+
+/// A `## Term` entry of GLOSSARY.md
+#[derive(Debug, Clone)]
+pub struct GlossaryTerm {
+    pub name: String,
+    /// Other forms after the name, e.g. the plural in `## API / APIs`
+    pub aliases: Vec<String>,
+    /// Fragment of the term's entry on the glossary page (e.g. "rest-api")
+    pub anchor: String,
+    /// Definition as written in GLOSSARY.md
+    pub markdown: String,
+    /// Text of the definition without Markdown, for search
+    pub definition: String,
+    /// `{case-sensitive}` or `{case-insensitive}` after the heading;
+    /// `glossary.caseSensitive` applies otherwise
+    pub case_sensitive: Option<bool>,
 }
 
 impl Glossary {
-    /// Load glossary from GLOSSARY.md file
-    pub fn load(book_dir: &Path) -> Result<Self> {
-        let glossary_path = book_dir.join("GLOSSARY.md");
+    /// Load glossary from the GLOSSARY.md file (`structure.glossary`)
+    pub fn load(book_dir: &Path, file_name: &str) -> Result<Self> {
+        let glossary_path = book_dir.join(file_name);
         if !glossary_path.exists() {
             return Ok(Self::default());
         }
@@ -26,178 +49,374 @@ impl Glossary {
 
     /// Parse GLOSSARY.md content
     pub fn parse(content: &str) -> Result<Self> {
-        let mut entries = HashMap::new();
-        let mut current_term: Option<String> = None;
-        let mut current_definition = String::new();
+        let mut title = None;
+        let mut terms: Vec<GlossaryTerm> = Vec::new();
+        let mut current_heading: Option<&str> = None;
+        let mut current_lines: Vec<&str> = Vec::new();
 
         for line in content.lines() {
             let trimmed = line.trim();
 
-            // Skip the main heading (# GLOSSARY)
-            if trimmed.starts_with("# ") {
+            // The main heading (# GLOSSARY) is the page title
+            if let Some(heading) = trimmed.strip_prefix("# ") {
+                title.get_or_insert_with(|| heading.trim().to_string());
                 continue;
             }
 
             // Check for term heading (## Term)
-            if trimmed.starts_with("## ") {
+            if let Some(heading) = trimmed.strip_prefix("## ") {
                 // Save previous entry if exists
-                if let Some(term) = current_term.take() {
-                    let definition = current_definition.trim().to_string();
-                    if !definition.is_empty() {
-                        entries.insert(term, definition);
-                    }
+                if let Some(heading) = current_heading.take() {
+                    terms.extend(parse_term(heading, &current_lines, &terms));
                 }
 
                 // Start new entry
-                current_term = Some(trimmed[3..].trim().to_string());
-                current_definition.clear();
+                current_heading = Some(heading);
+                current_lines.clear();
                 continue;
             }
 
             // Accumulate definition lines
-            if current_term.is_some() && !trimmed.is_empty() {
-                if !current_definition.is_empty() {
-                    current_definition.push(' ');
-                }
-                current_definition.push_str(trimmed);
+            if current_heading.is_some() {
+                current_lines.push(line);
             }
         }
 
         // Save last entry
-        if let Some(term) = current_term {
-            let definition = current_definition.trim().to_string();
-            if !definition.is_empty() {
-                entries.insert(term, definition);
-            }
+        if let Some(heading) = current_heading {
+            terms.extend(parse_term(heading, &current_lines, &terms));
         }
 
-        // Sort terms by length (longest first) to avoid partial replacements
-        let mut sorted_terms: Vec<String> = entries.keys().cloned().collect();
-        sorted_terms.sort_by(|a, b| b.len().cmp(&a.len()));
+        // Sort names and aliases by length (longest first) to avoid partial replacements
+        let mut sorted_terms: Vec<(String, usize)> = terms
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, term)| {
+                std::iter::once(&term.name)
+                    .chain(&term.aliases)
+                    .map(move |form| (form.clone(), idx))
+            })
+            .collect();
+        sorted_terms.sort_by_key(|(form, _)| std::cmp::Reverse(form.chars().count()));
 
         Ok(Self {
-            entries,
+            title,
+            terms,
             sorted_terms,
         })
     }
 
     /// Check if glossary is empty
     pub fn is_empty(&self) -> bool {
-        self.entries.is_empty()
+        self.terms.is_empty()
     }
 
-    /// Get definition for a term
+    /// Get definition for a term, by its name or an alias
+    #[cfg(test)]
     pub fn get(&self, term: &str) -> Option<&String> {
-        self.entries.get(term)
+        self.sorted_terms
+            .iter()
+            .find(|(form, _)| form == term)
+            .map(|&(_, idx)| &self.terms[idx].definition)
     }
 }
 
-/// Apply glossary terms to HTML content
-/// Wraps matching terms in <span class="glossary-term" data-definition="...">
-pub fn apply_glossary(html: &str, glossary: &Glossary) -> String {
-    if glossary.is_empty() {
-        return html.to_string();
+/// A term from its heading (`API / APIs {case-insensitive}`) and definition
+/// lines. Terms without a definition are skipped
+fn parse_term(heading: &str, lines: &[&str], terms: &[GlossaryTerm]) -> Option<GlossaryTerm> {
+    let markdown = lines.join("\n").trim().to_string();
+    if markdown.is_empty() {
+        return None;
+    }
+
+    let mut heading = heading.trim();
+    let mut case_sensitive = None;
+    if let Some((names, attributes)) = heading.strip_suffix('}').and_then(|rest| rest.rsplit_once('{')) {
+        for attribute in attributes.split_whitespace() {
+            match attribute {
+                "case-sensitive" => case_sensitive = Some(true),
+                "case-insensitive" => case_sensitive = Some(false),
+                _ => {}
+            }
+        }
+        heading = names.trim_end();
     }
 
-    let mut result = html.to_string();
+    // " / " separates forms, so "TCP/IP" stays one term
+    let mut forms = heading.split(" / ").map(str::trim).filter(|form| !form.is_empty()).map(String::from);
+    let name = forms.next()?;
+    let aliases = forms.collect();
+
+    Some(GlossaryTerm {
+        anchor: unique_anchor(&name, terms),
+        name,
+        aliases,
+        definition: plain_text(&markdown),
+        markdown,
+        case_sensitive,
+    })
+}
 
-    // Process each term (longest first to avoid partial replacements)
-    for term in &glossary.sorted_terms {
-        if let Some(definition) = glossary.get(term) {
-            result = replace_term_in_html(&result, term, definition);
+/// Text of Markdown content, with blocks and line breaks joined by spaces
+fn plain_text(markdown: &str) -> String {
+    let mut text = String::new();
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Text(t) | Event::Code(t) => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak | Event::End(TagEnd::Paragraph | TagEnd::Item) => text.push(' '),
+            _ => {}
         }
     }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Anchor of a term: lowercase words joined with `-` (like heading ids),
+/// with a number added when another term already has it
+fn unique_anchor(term: &str, terms: &[GlossaryTerm]) -> String {
+    let slug = term
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || (c.is_ascii_punctuation() && c != '_'))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug = if slug.is_empty() { "term".to_string() } else { slug };
+
+    let mut anchor = slug.clone();
+    let mut n = 1;
+    while terms.iter().any(|t| t.anchor == anchor) {
+        anchor = format!("{}-{}", slug, n);
+        n += 1;
+    }
+    anchor
+}
 
-    result
+/// Id of the element holding a term's definition on a page
+pub fn definition_id(term: &GlossaryTerm) -> String {
+    format!("glossary-def-{}", term.anchor)
 }
 
-/// Replace a term in HTML content, avoiding replacements inside:
+/// Page HTML with glossary terms marked up, and the terms it uses
+#[derive(Debug, Clone)]
+pub struct GlossaryMarkup {
+    pub html: String,
+    /// Names of the terms marked up, in order of first use
+    pub terms: Vec<String>,
+}
+
+/// Elements without a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+/// Apply glossary terms to HTML content
+///
+/// Wraps matching names and aliases in <a class="glossary-term"
+/// href="{link}#{anchor}" aria-describedby="glossary-def-{anchor}">, where
+/// `link` is the glossary page relative to the page; the page is expected
+/// to hold the definition under that id (see `definition_id`). Terms inside
+/// other links, or with no glossary page, are wrapped in a <span> instead.
+/// Nothing is replaced inside:
 /// - HTML tags
-/// - Existing glossary spans
+/// - Existing glossary terms
 /// - Code blocks (<code>, <pre>)
-/// - Already processed terms
-fn replace_term_in_html(html: &str, term: &str, definition: &str) -> String {
-    let mut result = String::new();
-    let mut chars = html.char_indices().peekable();
+/// - Elements listed in `glossary.exclude`
+/// - `<!-- glossary:off -->` ... `<!-- glossary:on -->` regions
+pub fn apply_glossary(html: &str, glossary: &Glossary, config: &GlossaryConfig, link: Option<&str>) -> GlossaryMarkup {
+    let mut markup = GlossaryMarkup {
+        html: String::with_capacity(html.len()),
+        terms: Vec::new(),
+    };
+    if glossary.is_empty() {
+        markup.html.push_str(html);
+        return markup;
+    }
+
+    let result = &mut markup.html;
+    let mut marked = vec![false; glossary.terms.len()];
     let mut in_tag = false;
-    let mut in_code = false;
-    let mut in_glossary_span = false;
+    let mut in_glossary_term = false;
+    // Inside a link, where a nested link isn't allowed
+    let mut in_link = false;
+    // Between <!-- glossary:off --> and <!-- glossary:on -->
+    let mut off = false;
+    // Open elements, and whether each one is excluded
+    let mut open_elements: Vec<(String, bool)> = Vec::new();
+    let mut excluded = 0;
     let mut tag_content = String::new();
+    let mut i = 0;
+
+    while let Some(c) = html[i..].chars().next() {
+        // A comment, which may switch marking off or on
+        if !in_tag && html[i..].starts_with("<!--") {
+            let end = html[i..].find("-->").map_or(html.len(), |end| i + end + 3);
+            match html[i + 4..end].trim_end_matches("-->").trim() {
+                "glossary:off" => off = true,
+                "glossary:on" => off = false,
+                _ => {}
+            }
+            result.push_str(&html[i..end]);
+            i = end;
+            continue;
+        }
 
-    while let Some((i, c)) = chars.next() {
         // Check if we're entering an HTML tag
         if c == '<' {
             in_tag = true;
             tag_content.clear();
-            result.push(c);
-            continue;
-        }
-
-        // Check if we're exiting an HTML tag
-        if c == '>' && in_tag {
+        } else if c == '>' && in_tag {
+            // Exiting an HTML tag: check its type
             in_tag = false;
-            result.push(c);
-
-            // Check tag type
             let tag_lower = tag_content.to_lowercase();
-            if tag_lower.starts_with("code") || tag_lower.starts_with("pre") {
-                in_code = true;
-            } else if tag_lower.starts_with("/code") || tag_lower.starts_with("/pre") {
-                in_code = false;
-            } else if tag_lower.starts_with("span") && tag_lower.contains("glossary-term") {
-                in_glossary_span = true;
-            } else if tag_lower.starts_with("/span") && in_glossary_span {
-                in_glossary_span = false;
+            let tag_name = tag_lower.split(|c: char| c.is_whitespace() || c == '/').find(|s| !s.is_empty()).unwrap_or("");
+            let closing = tag_lower.starts_with('/');
+            match (tag_name, closing) {
+                ("span" | "a", false) if tag_lower.contains("glossary-term") => in_glossary_term = true,
+                ("span" | "a", true) if in_glossary_term => in_glossary_term = false,
+                ("a", false) => in_link = true,
+                ("a", true) => in_link = false,
+                _ => {}
             }
-            continue;
-        }
-
-        // Collect tag content
-        if in_tag {
+            if closing {
+                // Close the element, and any left open inside it
+                if let Some(pos) = open_elements.iter().rposition(|(name, _)| name == tag_name) {
+                    excluded -= open_elements[pos..].iter().filter(|(_, is_excluded)| *is_excluded).count();
+                    open_elements.truncate(pos);
+                }
+            } else if !tag_name.is_empty() && !tag_lower.ends_with('/') && !VOID_ELEMENTS.contains(&tag_name) {
+                let is_excluded = matches!(tag_name, "code" | "pre" | "button") || is_excluded(tag_name, &tag_content, &config.exclude);
+                if is_excluded {
+                    excluded += 1;
+                }
+                open_elements.push((tag_name.to_string(), is_excluded));
+            }
+        } else if in_tag {
             tag_content.push(c);
-            result.push(c);
-            continue;
-        }
-
-        // Skip replacement inside code blocks or existing glossary spans
-        if in_code || in_glossary_span {
-            result.push(c);
-            continue;
-        }
-
-        // Check if the term starts here
-        if html[i..].starts_with(term) {
-            // Make sure it's a word boundary (not part of a larger word)
-            let before_ok = i == 0 || !is_word_char(result.chars().last().unwrap_or(' '));
-            let after_idx = i + term.len();
-            let after_ok = after_idx >= html.len()
-                || !is_word_char(html[after_idx..].chars().next().unwrap_or(' '));
-
-            if before_ok && after_ok {
-                // Escape definition for HTML attribute
-                let escaped_def = html_escape_attribute(definition);
-                result.push_str(&format!(
-                    r#"<span class="glossary-term" data-definition="{}">{}</span>"#,
-                    escaped_def, term
-                ));
-
-                // Skip the term characters
-                for _ in 0..term.len() - 1 {
-                    chars.next();
+        } else if !off && excluded == 0 && !in_glossary_term {
+            // Check if a term starts here
+            if let Some((idx, len)) = match_term(html, i, glossary, config) {
+                let term = &glossary.terms[idx];
+                let text = &html[i..i + len];
+                if config.first_only && marked[idx] {
+                    result.push_str(text);
+                } else {
+                    if !marked[idx] {
+                        marked[idx] = true;
+                        markup.terms.push(term.name.clone());
+                    }
+                    let describedby = definition_id(term);
+                    match link.filter(|_| !in_link) {
+                        Some(link) => result.push_str(&format!(
+                            r#"<a class="glossary-term" href="{}#{}" aria-describedby="{}">{}</a>"#,
+                            html_escape_attribute(link),
+                            html_escape_attribute(&term.anchor),
+                            html_escape_attribute(&describedby),
+                            text
+                        )),
+                        None => result.push_str(&format!(
+                            r#"<span class="glossary-term" aria-describedby="{}">{}</span>"#,
+                            html_escape_attribute(&describedby),
+                            text
+                        )),
+                    }
                 }
+                i += len;
                 continue;
             }
         }
 
         result.push(c);
+        i += c.len_utf8();
     }
 
-    result
+    markup
 }
 
-/// Check if a character is a word character (alphanumeric or Japanese)
+/// Whether an element is listed in `glossary.exclude`, by tag name or by
+/// one of its classes (`.name`)
+fn is_excluded(tag_name: &str, tag_content: &str, exclude: &[String]) -> bool {
+    if exclude.is_empty() {
+        return false;
+    }
+    let classes: Vec<&str> = class_attribute(tag_content).map(|value| value.split_whitespace().collect()).unwrap_or_default();
+    exclude.iter().any(|entry| match entry.strip_prefix('.') {
+        Some(class) => classes.contains(&class),
+        None => entry.eq_ignore_ascii_case(tag_name),
+    })
+}
+
+/// `class="..."`, `class='...'` or an unquoted `class=...` attribute
+static CLASS_ATTRIBUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\sclass\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap());
+
+/// Value of the `class` attribute of a tag (without its `<` and `>`)
+fn class_attribute(tag_content: &str) -> Option<&str> {
+    if !tag_content.to_lowercase().contains("class") {
+        return None;
+    }
+    let caps = CLASS_ATTRIBUTE.captures(tag_content)?;
+    caps.get(1).or(caps.get(2)).or(caps.get(3)).map(|value| value.as_str())
+}
+
+/// The longest name or alias starting at `start` with word boundaries on
+/// both sides: (term index, matched length in bytes)
+fn match_term(html: &str, start: usize, glossary: &Glossary, config: &GlossaryConfig) -> Option<(usize, usize)> {
+    let before = html[..start].chars().next_back();
+    let rest = &html[start..];
+
+    glossary.sorted_terms.iter().find_map(|(form, idx)| {
+        let case_sensitive = glossary.terms[*idx].case_sensitive.unwrap_or(config.case_sensitive);
+        let len = match_prefix(rest, form, case_sensitive)?;
+        let first = form.chars().next()?;
+        let last = form.chars().next_back()?;
+        let after = rest[len..].chars().next();
+        (is_boundary(before, first) && is_boundary(after, last)).then_some((*idx, len))
+    })
+}
+
+/// Length in bytes of `form` at the start of `text`
+fn match_prefix(text: &str, form: &str, case_sensitive: bool) -> Option<usize> {
+    if case_sensitive {
+        return text.starts_with(form).then_some(form.len());
+    }
+    let mut chars = text.char_indices();
+    for expected in form.chars() {
+        let (_, c) = chars.next()?;
+        if !c.to_lowercase().eq(expected.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(chars.next().map_or(text.len(), |(end, _)| end))
+}
+
+/// Whether a term can start or end next to `outside`: it must not continue a
+/// word, except in scripts written without spaces between words, where a
+/// term is found inside a run of text (Japanese "このAPIを", Chinese "用语表")
+fn is_boundary(outside: Option<char>, edge: char) -> bool {
+    match outside {
+        None => true,
+        Some(c) => !is_word_char(c) || is_cjk(c) || is_cjk(edge),
+    }
+}
+
+/// Check if a character is part of a word
 fn is_word_char(c: char) -> bool {
-    c.is_alphanumeric() || c > '\x7F'  // Japanese characters
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Han, kana, Hangul and Thai: scripts whose words aren't separated by spaces
+/// (Korean particles attach to the word before them)
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x0E00..=0x0E7F     // Thai
+        | 0x1100..=0x11FF   // Hangul Jamo
+        | 0x3040..=0x30FF   // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF   // Hangul Syllables
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+        | 0xFF66..=0xFF9F   // Halfwidth Katakana
+        | 0x20000..=0x2FA1F // CJK Extensions B-F
+    )
 }
 
 /// Escape a string for use in an HTML attribute
@@ -224,7 +443,7 @@ Software Development Kit の略
 "#;
 
         let glossary = Glossary::parse(content).unwrap();
-        assert_eq!(glossary.entries.len(), 2);
+        assert_eq!(glossary.terms.len(), 2);
         assert_eq!(
             glossary.get("API"),
             Some(&"Application Programming Interface の略".to_string())
@@ -255,15 +474,15 @@ Web APIの設計スタイルの一つ。
     fn test_apply_glossary() {
         let glossary = Glossary::parse("## API\nInterface").unwrap();
         let html = "<p>This is an API example.</p>";
-        let result = apply_glossary(html, &glossary);
-        assert!(result.contains(r#"<span class="glossary-term" data-definition="Interface">API</span>"#));
+        let result = apply_glossary(html, &glossary, &GlossaryConfig::default(), None).html;
+        assert!(result.contains(r#"<span class="glossary-term" aria-describedby="glossary-def-api">API</span>"#));
     }
 
     #[test]
     fn test_apply_glossary_in_code() {
         let glossary = Glossary::parse("## API\nInterface").unwrap();
         let html = "<p>Use the <code>API</code> endpoint.</p>";
-        let result = apply_glossary(html, &glossary);
+        let result = apply_glossary(html, &glossary, &GlossaryConfig::default(), None).html;
         // API inside code should not be wrapped
         assert!(result.contains("<code>API</code>"));
         assert!(!result.contains("glossary-term"));
@@ -273,7 +492,7 @@ Web APIの設計スタイルの一つ。
     fn test_apply_glossary_word_boundary() {
         let glossary = Glossary::parse("## API\nInterface").unwrap();
         let html = "<p>The APIARY tool is different from API.</p>";
-        let result = apply_glossary(html, &glossary);
+        let result = apply_glossary(html, &glossary, &GlossaryConfig::default(), None).html;
         // APIARY should not be affected
         assert!(result.contains("APIARY"));
         // But standalone API should be wrapped
@@ -302,6 +521,100 @@ Representational State Transfer
 
         let glossary = Glossary::parse(content).unwrap();
         // REST API should come before REST and API
-        assert_eq!(glossary.sorted_terms[0], "REST API");
+        assert_eq!(glossary.sorted_terms[0].0, "REST API");
+    }
+
+    #[test]
+    fn test_glossary_page_terms() {
+        let content = "# Terms\n\n## REST API\nAn API in the *REST* style.\n\n- Stateless\n\n## REST-API\nSame slug\n";
+        let glossary = Glossary::parse(content).unwrap();
+        assert_eq!(glossary.title.as_deref(), Some("Terms"));
+        let anchors: Vec<_> = glossary.terms.iter().map(|t| t.anchor.as_str()).collect();
+        assert_eq!(anchors, ["rest-api", "rest-api-1"]);
+        assert_eq!(glossary.terms[0].markdown, "An API in the *REST* style.\n\n- Stateless");
+        assert_eq!(glossary.get("REST API"), Some(&"An API in the REST style. Stateless".to_string()));
+    }
+
+    #[test]
+    fn test_apply_glossary_links() {
+        let glossary = Glossary::parse("## REST API\nInterface\n\n## 用語\n定義").unwrap();
+        let html = r#"<p>A REST API, <a href="x.html">the REST API</a> and 用語.</p>"#;
+        let markup = apply_glossary(html, &glossary, &GlossaryConfig::default(), Some("../GLOSSARY.html"));
+        assert!(markup.html.contains(
+            r#"A <a class="glossary-term" href="../GLOSSARY.html#rest-api" aria-describedby="glossary-def-rest-api">REST API</a>,"#
+        ));
+        // No link inside another link
+        assert!(markup.html.contains(
+            r#"<a href="x.html">the <span class="glossary-term" aria-describedby="glossary-def-rest-api">REST API</span></a>"#
+        ));
+        assert!(markup.html.contains(r#"href="../GLOSSARY.html#用語" aria-describedby="glossary-def-用語">用語</a>."#));
+        assert_eq!(markup.terms, ["REST API", "用語"]);
+    }
+
+    #[test]
+    fn test_aliases_and_case() {
+        let content = "## API / APIs\nInterface\n\n## TCP/IP\nProtocols\n\n## Widget {case-insensitive}\nA control\n";
+        let glossary = Glossary::parse(content).unwrap();
+        assert_eq!(glossary.terms[0].aliases, ["APIs"]);
+        assert_eq!(glossary.terms[1].name, "TCP/IP");
+        assert_eq!(glossary.terms[2].name, "Widget");
+        assert_eq!(glossary.terms[2].case_sensitive, Some(false));
+        assert_eq!(glossary.get("APIs"), Some(&"Interface".to_string()));
+
+        let config = GlossaryConfig::default();
+        let markup = apply_glossary("<p>APIs, api, widgets, widget, TCP/IP</p>", &glossary, &config, None);
+        assert_eq!(
+            markup.html,
+            concat!(
+                r#"<p><span class="glossary-term" aria-describedby="glossary-def-api">APIs</span>, api, widgets, "#,
+                r#"<span class="glossary-term" aria-describedby="glossary-def-widget">widget</span>, "#,
+                r#"<span class="glossary-term" aria-describedby="glossary-def-tcp-ip">TCP/IP</span></p>"#
+            )
+        );
+
+        let config = GlossaryConfig { case_sensitive: false, ..Default::default() };
+        let markup = apply_glossary("<p>api</p>", &glossary, &config, None);
+        assert!(markup.html.contains(">api</span>"));
+    }
+
+    #[test]
+    fn test_first_only() {
+        let glossary = Glossary::parse("## API / APIs\nInterface\n\n## REST API\nStyle").unwrap();
+        let config = GlossaryConfig { first_only: true, ..Default::default() };
+        let markup = apply_glossary("<p>API, APIs and a REST API</p>", &glossary, &config, None);
+        assert_eq!(markup.html.matches("glossary-term").count(), 2);
+        // The later "REST API" isn't split to mark "API" again
+        assert!(markup.html.ends_with(r#"and a <span class="glossary-term" aria-describedby="glossary-def-rest-api">REST API</span></p>"#));
+        assert_eq!(markup.terms, ["API", "REST API"]);
+    }
+
+    #[test]
+    fn test_cjk_matching() {
+        let glossary = Glossary::parse("## API\nInterface\n\n## 用語\n定義\n\n## 서버\n컴퓨터").unwrap();
+        let config = GlossaryConfig::default();
+        let markup = apply_glossary("<p>このAPIを使う用語集。서버를 켜다. APIv2</p>", &glossary, &config, None);
+        assert_eq!(markup.terms, ["API", "用語", "서버"]);
+        assert!(markup.html.contains(r#"この<span class="glossary-term" aria-describedby="glossary-def-api">API</span>を"#));
+        assert!(markup.html.ends_with(" APIv2</p>"));
+    }
+
+    #[test]
+    fn test_exclusion_zones() {
+        let glossary = Glossary::parse("## API\nInterface").unwrap();
+        let config = GlossaryConfig {
+            exclude: vec!["h2".to_string(), ".note".to_string()],
+            ..Default::default()
+        };
+        let html = concat!(
+            "<h2 id=\"api\">API</h2>\n",
+            "<div class=\"box note\"><p>API <div>nested</div> API</p></div>\n",
+            "<button type=\"button\" role=\"tab\">API</button>\n",
+            "<p>API<!-- glossary:off --> API <em>API</em><!-- glossary:on --> API<br>API</p>\n",
+        );
+        let markup = apply_glossary(html, &glossary, &config, None);
+        let marked: Vec<&str> = markup.html.lines().map(|line| if line.contains("glossary-term") { "marked" } else { "plain" }).collect();
+        assert_eq!(marked, ["plain", "plain", "plain", "marked"]);
+        assert_eq!(markup.html.lines().last().unwrap().matches("glossary-term").count(), 3);
+        assert!(markup.html.contains("<!-- glossary:off --> API <em>API</em><!-- glossary:on -->"));
     }
 }
@@ -0,0 +1,384 @@
+//! book.js reader
+//!
+//! HonKit runs book.js with Node. guidebook doesn't run JavaScript; it reads
+//! book.js only when the exported config is plain data:
+//!
+//! ```js
+//! const variables = { version: '1.0' };
+//!
+//! module.exports = {
+//!     title: 'My Book',
+//!     plugins: ['back-to-top-button'],
+//!     variables,
+//! };
+//! ```
+//!
+//! The file may hold `const` declarations and one `module.exports = ...`.
+//! Values are object and array literals, quoted strings, numbers, booleans,
+//! `null` and names of earlier `const` values. Anything else (`require`,
+//! function calls, `process.env`, template strings, spreads) is an error that
+//! names what was found.
+
+use anyhow::{bail, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Read the config exported by book.js into a JSON value
+pub fn parse(content: &str) -> Result<Value> {
+    let mut parser = Parser {
+        chars: content.chars().collect(),
+        pos: 0,
+        constants: HashMap::new(),
+    };
+    let mut exports = None;
+
+    loop {
+        parser.skip_trivia();
+        if parser.peek().is_none() {
+            break;
+        }
+
+        if parser.keyword("module.exports") {
+            parser.expect('=')?;
+            exports = Some(parser.value()?);
+        } else if parser.keyword("const") {
+            let name = parser.identifier()?;
+            parser.expect('=')?;
+            let value = parser.value()?;
+            parser.constants.insert(name, value);
+        } else {
+            return parser.unsupported();
+        }
+
+        parser.skip_trivia();
+        parser.eat(';');
+    }
+
+    match exports {
+        Some(value) => Ok(value),
+        None => bail!("book.js doesn't set `module.exports`"),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Values of the `const` declarations seen so far
+    constants: HashMap<String, Value>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        self.skip_trivia();
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.error(&format!("expected `{}`", c))
+        }
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T> {
+        let before = &self.chars[..self.pos.min(self.chars.len())];
+        let line = before.iter().filter(|&&c| c == '\n').count() + 1;
+        let column = before.iter().rev().take_while(|&&c| c != '\n').count() + 1;
+        bail!("{}:{}: {}", line, column, message)
+    }
+
+    /// Error for anything outside the plain-data subset
+    fn unsupported<T>(&self) -> Result<T> {
+        let found: String = self.chars[self.pos..]
+            .iter()
+            .take_while(|c| !c.is_whitespace() && !matches!(c, '(' | ';' | ','))
+            .take(30)
+            .collect();
+        self.error(&format!(
+            "`{}` isn't plain data; book.js must export plain data (or convert it to book.json)",
+            found
+        ))
+    }
+
+    /// Skip whitespace and comments
+    fn skip_trivia(&mut self) {
+        loop {
+            while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+                self.pos += 1;
+            }
+            let rest = &self.chars[self.pos..];
+            if rest.starts_with(&['/', '/']) {
+                while !matches!(self.peek(), None | Some('\n')) {
+                    self.pos += 1;
+                }
+            } else if rest.starts_with(&['/', '*']) {
+                self.pos += 2;
+                while self.peek().is_some() && !self.chars[self.pos..].starts_with(&['*', '/']) {
+                    self.pos += 1;
+                }
+                self.pos = (self.pos + 2).min(self.chars.len());
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Consume `word` (which may contain dots) when it's next as a whole word
+    fn keyword(&mut self, word: &str) -> bool {
+        self.skip_trivia();
+        let len = word.chars().count();
+        let matches = self.chars[self.pos..].iter().take(len).copied().eq(word.chars());
+        let whole = !matches!(self.chars.get(self.pos + len), Some(&c) if is_identifier_char(c));
+        if matches && whole {
+            self.pos += len;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String> {
+        self.skip_trivia();
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if is_identifier_char(c)) {
+            self.pos += 1;
+        }
+        if start == self.pos || self.chars[start].is_ascii_digit() {
+            self.pos = start;
+            return self.error("expected a name");
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        self.skip_trivia();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                self.object()
+            }
+            Some('[') => {
+                self.pos += 1;
+                self.array()
+            }
+            Some('"' | '\'') => self.string().map(Value::String),
+            Some(c) if c.is_ascii_digit() || c == '-' => self.number(),
+            Some(c) if is_identifier_char(c) => {
+                let start = self.pos;
+                let name = self.identifier()?;
+                match name.as_str() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "null" => Ok(Value::Null),
+                    _ => {
+                        // A constant is only plain data when it isn't called or accessed
+                        self.skip_trivia();
+                        match self.constants.get(&name) {
+                            Some(value) if !matches!(self.peek(), Some('(' | '.' | '[')) => Ok(value.clone()),
+                            _ => {
+                                self.pos = start;
+                                self.unsupported()
+                            }
+                        }
+                    }
+                }
+            }
+            Some(_) => self.unsupported(),
+            None => self.error("expected a value"),
+        }
+    }
+
+    fn object(&mut self) -> Result<Value> {
+        let mut object = Map::new();
+        loop {
+            self.skip_trivia();
+            if self.eat('}') {
+                return Ok(Value::Object(object));
+            }
+            let key = match self.peek() {
+                Some('"' | '\'') => self.string()?,
+                Some(c) if is_identifier_char(c) => self.identifier()?,
+                _ => return self.unsupported(),
+            };
+            self.skip_trivia();
+            let value = if self.eat(':') {
+                self.value()?
+            } else if matches!(self.peek(), Some(',' | '}')) {
+                // Shorthand `{ variables }`
+                match self.constants.get(&key) {
+                    Some(value) => value.clone(),
+                    None => return self.error(&format!("`{}` is not a const defined earlier in book.js", key)),
+                }
+            } else {
+                return self.error("expected `:` after the key");
+            };
+            object.insert(key, value);
+
+            self.skip_trivia();
+            if !self.eat(',') {
+                self.skip_trivia();
+                if self.eat('}') {
+                    return Ok(Value::Object(object));
+                }
+                return self.error("expected `,` or `}` in the object");
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia();
+            if self.eat(']') {
+                return Ok(Value::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_trivia();
+            if !self.eat(',') {
+                self.skip_trivia();
+                if self.eat(']') {
+                    return Ok(Value::Array(items));
+                }
+                return self.error("expected `,` or `]` in the array");
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let quote = self.peek().unwrap();
+        self.pos += 1;
+        let mut s = String::new();
+        loop {
+            match self.peek() {
+                None | Some('\n') => return self.error("unterminated string"),
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    return Ok(s);
+                }
+                Some('\\') => {
+                    self.pos += 1;
+                    self.escape(&mut s)?;
+                }
+                Some(c) => {
+                    s.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// Append the character of an escape sequence (after the backslash)
+    fn escape(&mut self, s: &mut String) -> Result<()> {
+        let Some(c) = self.peek() else {
+            return self.error("unterminated string");
+        };
+        self.pos += 1;
+        match c {
+            'n' => s.push('\n'),
+            't' => s.push('\t'),
+            'r' => s.push('\r'),
+            'u' => {
+                let hex: String = self.chars[self.pos..].iter().take(4).collect();
+                self.pos += hex.chars().count();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(c) => s.push(c),
+                    None => return self.error("invalid unicode escape"),
+                }
+            }
+            other => s.push(other),
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<Value> {
+        let start = self.pos;
+        self.eat('-');
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-')) {
+            self.pos += 1;
+        }
+        let token: String = self.chars[start..self.pos].iter().collect();
+        let value = token
+            .parse::<i64>()
+            .map(Value::from)
+            .ok()
+            .or_else(|| token.parse::<f64>().ok().filter(|n| n.is_finite()).map(Value::from));
+        match value {
+            Some(value) => Ok(value),
+            None => {
+                self.pos = start;
+                self.error(&format!("invalid number `{}`", token))
+            }
+        }
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_book_js() {
+        let js = r#"
+// Shared settings
+const variables = { version: '1.0', year: 2024 };
+
+/* The config */
+module.exports = {
+    title: "My Book",
+    'plugins': ["back-to-top-button", '-mermaid-md-adoc'],
+    styles: { website: 'styles/website.css', },
+    variables,
+    theme: null,
+    ratio: -0.5,
+    hardbreaks: true,
+};
+"#;
+        assert_eq!(
+            parse(js).unwrap(),
+            json!({
+                "title": "My Book",
+                "plugins": ["back-to-top-button", "-mermaid-md-adoc"],
+                "styles": { "website": "styles/website.css" },
+                "variables": { "version": "1.0", "year": 2024 },
+                "theme": null,
+                "ratio": -0.5,
+                "hardbreaks": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_book_js_needs_plain_data() {
+        let err = parse("const pkg = require('./package.json');").unwrap_err().to_string();
+        assert!(err.starts_with("1:13: ") && err.contains("`require`"), "{}", err);
+
+        let err = parse("module.exports = { title: process.env.TITLE };").unwrap_err().to_string();
+        assert!(err.contains("`process.env.TITLE`"), "{}", err);
+
+        let err = parse("module.exports = { title: `v${version}` };").unwrap_err().to_string();
+        assert!(err.contains("``v${version}`` isn't plain data"), "{}", err);
+
+        let err = parse("const base = ['a'];\nmodule.exports = { plugins: [...base] };").unwrap_err().to_string();
+        assert!(err.starts_with("2:30: ") && err.contains("`...base]`"), "{}", err);
+
+        let err = parse("export default { title: 'ESM' }").unwrap_err().to_string();
+        assert!(err.contains("`export`"), "{}", err);
+
+        assert!(parse("const x = 1;").unwrap_err().to_string().contains("module.exports"));
+    }
+}
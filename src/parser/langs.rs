@@ -1,58 +1,163 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use regex::Regex;
 use std::fs;
 use std::path::Path;
+use std::sync::LazyLock;
+
+/// Primary language subtags that are written right-to-left
+const RTL_LANGUAGES: &[&str] = &["ar", "arc", "ckb", "dv", "fa", "he", "ku", "ps", "sd", "ug", "ur", "yi"];
 
 #[derive(Debug, Clone)]
 pub struct Language {
+    /// Directory of the language, also its URL path
     pub code: String,
     pub title: String,
+    /// BCP-47 language tag, the directory name unless set with `lang=`
+    pub lang: String,
+    /// "ltr" or "rtl" when set with `dir=`
+    pub direction: Option<String>,
+    /// Where the root page redirects when no browser language matches
+    pub default: bool,
+    /// Built, but left out of the language lists and the redirect
+    pub hidden: bool,
+}
+
+impl Language {
+    pub fn new(code: &str, title: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            title: title.to_string(),
+            lang: code.to_string(),
+            direction: None,
+            default: false,
+            hidden: false,
+        }
+    }
+
+    /// Whether the language is written right-to-left
+    pub fn is_rtl(&self) -> bool {
+        match &self.direction {
+            Some(direction) => direction == "rtl",
+            None => is_rtl_language(&self.lang),
+        }
+    }
 }
 
 /// Parse LANGS.md to get available languages
 /// Format:
 /// * [Japanese](jp/)
-/// * [Vietnamese](vn/)
-pub fn parse_langs(book_dir: &Path) -> Result<Vec<Language>> {
-    let langs_path = book_dir.join("LANGS.md");
+/// * [Português](pt/) {lang=pt-BR default}
+/// * [العربية](ar/) {dir=rtl hidden}
+pub fn parse_langs(book_dir: &Path, file_name: &str) -> Result<Vec<Language>> {
+    let langs_path = book_dir.join(file_name);
 
     if !langs_path.exists() {
         return Ok(Vec::new());
     }
 
-    let content = fs::read_to_string(&langs_path)?;
-    let mut languages = Vec::new();
+    let content = fs::read_to_string(&langs_path).with_context(|| format!("Failed to read {:?}", langs_path))?;
+    parse_langs_content(&content).with_context(|| format!("Invalid {}", file_name))
+}
 
-    for line in content.lines() {
-        let line = line.trim();
+/// Parse the links of the list items in LANGS.md content, each with an
+/// optional `{...}` attribute block after it
+pub fn parse_langs_content(content: &str) -> Result<Vec<Language>> {
+    let mut languages = Vec::new();
+    // Link of the list item being read: (destination, title, text after the link)
+    let mut current: Option<(String, String, String)> = None;
+    let mut in_link = false;
+    let mut item_depth = 0;
 
-        // Match pattern: * [Title](code/) or - [Title](code/)
-        if (line.starts_with('*') || line.starts_with('-')) && line.contains('[') && line.contains("](") {
-            if let Some(lang) = parse_lang_line(line) {
-                languages.push(lang);
+    for event in Parser::new(content) {
+        match event {
+            Event::Start(Tag::Item) => {
+                if let Some(link) = current.take() {
+                    languages.push(language(link)?);
+                }
+                item_depth += 1;
+            }
+            Event::End(TagEnd::Item) => {
+                if let Some(link) = current.take() {
+                    languages.push(language(link)?);
+                }
+                item_depth -= 1;
             }
+            Event::Start(Tag::List(_)) => {
+                // A nested list ends the text of its parent item
+                if let Some(link) = current.take() {
+                    languages.push(language(link)?);
+                }
+            }
+            Event::Start(Tag::Link { dest_url, .. }) if item_depth > 0 && current.is_none() => {
+                current = Some((dest_url.to_string(), String::new(), String::new()));
+                in_link = true;
+            }
+            Event::End(TagEnd::Link) => in_link = false,
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, title, after)) = current.as_mut() {
+                    if in_link {
+                        title.push_str(&text);
+                    } else {
+                        after.push_str(&text);
+                    }
+                }
+            }
+            _ => {}
         }
     }
 
+    if languages.iter().filter(|lang| lang.default).count() > 1 {
+        bail!("only one language can be marked `default`");
+    }
     Ok(languages)
 }
 
-fn parse_lang_line(line: &str) -> Option<Language> {
-    // Extract title between [ and ]
-    let title_start = line.find('[')? + 1;
-    let title_end = line.find(']')?;
-    let title = line[title_start..title_end].to_string();
+fn language((dest, title, after): (String, String, String)) -> Result<Language> {
+    let code = dest.trim_start_matches("./").trim_end_matches('/');
+    let mut lang = Language::new(code, title.trim());
 
-    // Extract code between ]( and )
-    let code_start = line.find("](")? + 2;
-    let code_end = line.rfind(')')?;
-    let mut code = line[code_start..code_end].to_string();
-
-    // Remove trailing slash if present
-    if code.ends_with('/') {
-        code.pop();
+    let after = after.trim();
+    let Some(attributes) = after.strip_prefix('{').and_then(|rest| rest.strip_suffix('}')) else {
+        return Ok(lang);
+    };
+    for attribute in attributes.split_whitespace() {
+        match attribute.split_once('=') {
+            Some(("lang", tag)) => {
+                if !is_language_tag(tag) {
+                    bail!("`{}` of {} is not a language tag like \"pt-BR\"", tag, code);
+                }
+                lang.lang = tag.to_string();
+            }
+            Some(("dir", direction)) => {
+                let direction = direction.to_lowercase();
+                if direction != "ltr" && direction != "rtl" {
+                    bail!("`dir` of {} must be \"ltr\" or \"rtl\"", code);
+                }
+                lang.direction = Some(direction);
+            }
+            None if attribute == "default" => lang.default = true,
+            None if attribute == "hidden" => lang.hidden = true,
+            _ => bail!(
+                "unknown attribute `{}` of {} (use lang=, dir=, default or hidden)",
+                attribute,
+                code
+            ),
+        }
     }
+    Ok(lang)
+}
+
+/// Loose BCP-47 check: a 2-8 letter primary subtag and alphanumeric subtags
+fn is_language_tag(tag: &str) -> bool {
+    static TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[A-Za-z]{2,8}(-[A-Za-z0-9]{1,8})*$").unwrap());
+    TAG.is_match(tag)
+}
 
-    Some(Language { code, title })
+/// Check if a language code (e.g. "ar", "he-IL", "fa_IR") is written right-to-left
+pub fn is_rtl_language(code: &str) -> bool {
+    let primary = code.split(['-', '_']).next().unwrap_or("").to_lowercase();
+    RTL_LANGUAGES.contains(&primary.as_str())
 }
 
 #[cfg(test)]
@@ -60,13 +165,43 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_lang_line() {
-        let lang = parse_lang_line("* [Japanese](jp/)").unwrap();
-        assert_eq!(lang.code, "jp");
-        assert_eq!(lang.title, "Japanese");
-
-        let lang = parse_lang_line("- [Vietnamese](vn/)").unwrap();
-        assert_eq!(lang.code, "vn");
-        assert_eq!(lang.title, "Vietnamese");
+    fn test_parse_langs() {
+        let langs = parse_langs_content("# Languages\n\n* [Japanese](jp/)\n- [Vietnamese](./vn)\n* [`English`](en/)\n").unwrap();
+        let codes: Vec<_> = langs.iter().map(|lang| (lang.code.as_str(), lang.title.as_str())).collect();
+        assert_eq!(codes, [("jp", "Japanese"), ("vn", "Vietnamese"), ("en", "English")]);
+        assert_eq!(langs[0].lang, "jp");
+        assert!(!langs[0].default && !langs[0].hidden);
+
+        // Links outside lists aren't languages
+        assert!(parse_langs_content("See [the guide](guide.md).\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_lang_attributes() {
+        let langs = parse_langs_content(
+            "* [English](en/) {default}\n* [Português](pt/) {lang=pt-BR}\n* [Draft](xx/) {dir=RTL hidden}\n",
+        )
+        .unwrap();
+        assert!(langs[0].default);
+        assert_eq!(langs[1].lang, "pt-BR");
+        assert_eq!(langs[2].direction.as_deref(), Some("rtl"));
+        assert!(langs[2].hidden && langs[2].is_rtl());
+
+        let err = parse_langs_content("* [A](a/) {default}\n* [B](b/) {default}\n").unwrap_err();
+        assert!(err.to_string().contains("only one"), "{}", err);
+        let err = parse_langs_content("* [A](a/) {lang=pt_BR!}\n").unwrap_err();
+        assert!(err.to_string().contains("not a language tag"), "{}", err);
+        let err = parse_langs_content("* [A](a/) {hiden}\n").unwrap_err();
+        assert!(err.to_string().contains("unknown attribute `hiden`"), "{}", err);
+    }
+
+    #[test]
+    fn test_is_rtl_language() {
+        assert!(is_rtl_language("ar"));
+        assert!(is_rtl_language("he-IL"));
+        assert!(is_rtl_language("fa_IR"));
+        assert!(!is_rtl_language("ja"));
+        assert!(!is_rtl_language("en-US"));
+        assert!(!is_rtl_language(""));
     }
 }
@@ -1,11 +1,15 @@
 pub mod book_config;
+pub mod compat;
+pub mod config_file;
 pub mod frontmatter;
 pub mod glossary;
+mod js_config;
 pub mod langs;
+pub mod schema;
 pub mod summary;
 
 pub use book_config::BookConfig;
 pub use frontmatter::{parse_front_matter, FrontMatter};
-pub use glossary::{apply_glossary, Glossary};
+pub use glossary::Glossary;
 pub use langs::Language;
 pub use summary::{Summary, SummaryItem};
@@ -0,0 +1,234 @@
+//! JSON Schema of the book configuration
+//!
+//! schema/book.schema.json is published for editors and checked here before
+//! the config is deserialized, so a wrong type is reported with the key it is
+//! about and a way to fix it. Only the schema keywords the file uses are
+//! supported: `type`, `enum`, `properties`, `additionalProperties`, `items`
+//! and `$ref` to `#/definitions`.
+
+use crate::parser::compat::{suggest, Finding};
+use serde_json::Value;
+use std::sync::LazyLock;
+
+/// The schema, as printed by `guidebook config schema`
+pub const BOOK_SCHEMA: &str = include_str!("../../schema/book.schema.json");
+
+/// The schema, parsed on first use
+pub static SCHEMA: LazyLock<Value> =
+    LazyLock::new(|| serde_json::from_str(BOOK_SCHEMA).expect("built-in schema is valid JSON"));
+
+/// Check a config value against the schema
+pub fn validate(config: &Value) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    check(config, &SCHEMA, &SCHEMA, &mut Vec::new(), &mut findings);
+    findings
+}
+
+/// Names of the `properties` of a schema object
+pub fn property_names(schema: &Value) -> Vec<&str> {
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|properties| properties.keys().map(String::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn check(value: &Value, schema: &Value, root: &Value, key: &mut Vec<String>, findings: &mut Vec<Finding>) {
+    let schema = resolve(schema, root);
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.iter().any(|name| has_type(value, name)) {
+            let mut message = format!("expected {}, found {}", describe_types(&types, schema), describe(value));
+            if let Some(fix) = type_fix(value, &types) {
+                message.push_str(&format!(". Use {}", fix));
+            }
+            findings.push(finding(key, message));
+            return;
+        }
+    }
+
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            let names: Vec<&str> = options.iter().filter_map(Value::as_str).collect();
+            let mut message = format!("{} is not one of {}", describe(value), quote_list(&names));
+            if let Some(suggestion) = value.as_str().and_then(|s| suggest(s, &names)) {
+                message.push_str(&format!(". Did you mean \"{}\"?", suggestion));
+            }
+            findings.push(finding(key, message));
+            return;
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let additional = schema.get("additionalProperties").filter(|s| s.is_object());
+            for (name, nested) in object {
+                let Some(nested_schema) = properties.and_then(|p| p.get(name)).or(additional) else {
+                    // Unknown keys are listed by the compatibility report
+                    continue;
+                };
+                key.push(name.clone());
+                check(nested, nested_schema, root, key, findings);
+                key.pop();
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    key.push(index.to_string());
+                    check(item, item_schema, root, key, findings);
+                    key.pop();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Follow a `$ref` to `#/definitions/<name>`
+fn resolve<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    match schema.get("$ref").and_then(Value::as_str).and_then(|r| r.strip_prefix("#/definitions/")) {
+        Some(name) => root.pointer(&format!("/definitions/{}", name)).unwrap_or(schema),
+        None => schema,
+    }
+}
+
+fn finding(key: &[String], message: String) -> Finding {
+    let mut subject = String::new();
+    for part in key {
+        if part.chars().all(|c| c.is_ascii_digit()) {
+            subject.push_str(&format!("[{}]", part));
+        } else {
+            if !subject.is_empty() {
+                subject.push('.');
+            }
+            subject.push_str(part);
+        }
+    }
+    Finding {
+        key: key.to_vec(),
+        subject: format!("key `{}`", subject),
+        message,
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn describe_types(types: &[&str], schema: &Value) -> String {
+    let names: Vec<String> = types
+        .iter()
+        .filter(|name| **name != "null")
+        .map(|name| match *name {
+            "array" => match schema.pointer("/items/type").and_then(Value::as_str) {
+                Some(item) => format!("an array of {}s", item),
+                None => "an array".to_string(),
+            },
+            "object" => "an object".to_string(),
+            "integer" => "an integer".to_string(),
+            other => format!("a {}", other),
+        })
+        .collect();
+    names.join(" or ")
+}
+
+/// A value as the reader wrote it, e.g. `a string "mermaid"`
+fn describe(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("a string \"{}\"", s),
+        Value::Number(n) => format!("a number {}", n),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        Value::Array(_) => "an array".to_string(),
+        Value::Object(_) => "an object".to_string(),
+    }
+}
+
+/// How to write the value with the expected type, when it's obvious
+fn type_fix(value: &Value, types: &[&str]) -> Option<String> {
+    match value {
+        Value::String(s) if types.contains(&"array") => Some(format!("[\"{}\"]", s)),
+        Value::String(s) if types.contains(&"boolean") && (s == "true" || s == "false") => {
+            Some(format!("{} without quotes", s))
+        }
+        Value::String(s) if types.contains(&"number") && s.trim().parse::<f64>().is_ok() => {
+            Some(format!("{} without quotes", s.trim()))
+        }
+        Value::Number(_) | Value::Bool(_) if types.contains(&"string") => Some(format!("\"{}\"", value)),
+        _ => None,
+    }
+}
+
+fn quote_list(names: &[&str]) -> String {
+    names.iter().map(|name| format!("\"{}\"", name)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn check_messages(config: Value) -> Vec<String> {
+        validate(&config).iter().map(|finding| finding.to_string()).collect()
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let config = json!({
+            "$schema": "./schema/book.schema.json",
+            "title": "Book",
+            "plugins": ["back-to-top-button"],
+            "pluginsConfig": { "word-count": { "command": "./wc", "wpm": 200 } },
+            "language": null,
+            "themes": { "solarized": { "dark": true, "mermaid": "dark", "colors": { "background": "#002b36" } } },
+            "unknown": 1
+        });
+        assert!(check_messages(config).is_empty());
+    }
+
+    #[test]
+    fn test_wrong_types() {
+        let messages = check_messages(json!({
+            "plugins": "mermaid",
+            "hardbreaks": "true",
+            "title": 42,
+            "styles": { "website": ["a.css"] },
+            "search": { "global": "yes" }
+        }));
+        assert_eq!(messages, [
+            "key `hardbreaks`: expected a boolean, found a string \"true\". Use true without quotes",
+            "key `plugins`: expected an array of strings, found a string \"mermaid\". Use [\"mermaid\"]",
+            "key `search.global`: expected a boolean, found a string \"yes\"",
+            "key `styles.website`: expected a string, found an array",
+            "key `title`: expected a string, found a number 42. Use \"42\"",
+        ]);
+
+        let findings = validate(&json!({ "plugins": ["a", 1] }));
+        assert_eq!(findings[0].key, ["plugins", "1"]);
+        assert_eq!(findings[0].subject, "key `plugins[1]`");
+    }
+
+    #[test]
+    fn test_enum_suggestion() {
+        let messages = check_messages(json!({ "themes": { "mine": { "mermaid": "forrest" } } }));
+        assert_eq!(messages, [
+            "key `themes.mine.mermaid`: a string \"forrest\" is not one of \"default\", \"dark\", \"forest\", \"neutral\", \"base\". Did you mean \"forest\"?",
+        ]);
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use pulldown_cmark::{Event, Parser, Tag, TagEnd};
 use std::fs;
 use std::path::Path;
@@ -26,9 +26,10 @@ pub enum SummaryItem {
 }
 
 impl Summary {
-    pub fn parse(book_dir: &Path) -> Result<Self> {
-        let summary_path = book_dir.join("SUMMARY.md");
-        let content = fs::read_to_string(&summary_path)?;
+    pub fn parse(book_dir: &Path, file_name: &str) -> Result<Self> {
+        let summary_path = book_dir.join(file_name);
+        let content = fs::read_to_string(&summary_path)
+            .with_context(|| format!("Failed to read {:?}", summary_path))?;
         parse_summary(&content)
     }
 }
@@ -6,42 +6,11 @@
 
     // Storage keys
     var STORAGE_KEY_FONT_SIZE = 'guidebook-font-size';
-    var STORAGE_KEY_THEME = 'guidebook-theme';
 
     // Font size configuration
     var FONT_SIZES = [12, 14, 16, 18, 20, 22, 24];
     var DEFAULT_FONT_SIZE_INDEX = 2; // 16px
 
-    // Theme configuration
-    var THEMES = {
-        white: {
-            background: '#ffffff',
-            color: '#333333',
-            sidebarBg: '#fafafa',
-            sidebarBorder: '#e8e8e8',
-            codeBg: '#f6f8fa',
-            linkColor: '#4183c4'
-        },
-        sepia: {
-            background: '#f4ecd8',
-            color: '#5f4b32',
-            sidebarBg: '#ede6d4',
-            sidebarBorder: '#d4c9b0',
-            codeBg: '#ebe4d0',
-            linkColor: '#704214'
-        },
-        night: {
-            background: '#1c1c1c',
-            color: '#c8c8c8',
-            sidebarBg: '#262626',
-            sidebarBorder: '#3a3a3a',
-            codeBg: '#2d2d2d',
-            linkColor: '#6cb2eb'
-        }
-    };
-
-    var DEFAULT_THEME = 'white';
-
     // Get current font size index
     function getFontSizeIndex() {
         var stored = localStorage.getItem(STORAGE_KEY_FONT_SIZE);
@@ -54,15 +23,6 @@
         return DEFAULT_FONT_SIZE_INDEX;
     }
 
-    // Get current theme
-    function getTheme() {
-        var stored = localStorage.getItem(STORAGE_KEY_THEME);
-        if (stored && THEMES[stored]) {
-            return stored;
-        }
-        return DEFAULT_THEME;
-    }
-
     // Apply font size
     function applyFontSize(index) {
         var size = FONT_SIZES[index];
@@ -74,105 +34,12 @@
         updateFontButtons(index);
     }
 
-    // Apply theme
+    // Apply theme (colors are handled by theme.js)
     function applyTheme(themeName) {
-        var theme = THEMES[themeName];
-        if (!theme) return;
-
-        var book = document.querySelector('.book');
-        if (book) {
-            // Remove all theme classes
-            book.classList.remove('theme-white', 'theme-sepia', 'theme-night');
-            // Add new theme class
-            book.classList.add('theme-' + themeName);
+        if (window.guidebookTheme) {
+            window.guidebookTheme.select(themeName);
+            updateThemeButtons(window.guidebookTheme.getChoice());
         }
-
-        // Force table styles for night theme (overrides custom CSS)
-        applyTableStyles(themeName);
-
-        localStorage.setItem(STORAGE_KEY_THEME, themeName);
-
-        // Update button states
-        updateThemeButtons(themeName);
-    }
-
-    // Apply inline styles to override custom CSS for theme compatibility
-    function applyTableStyles(themeName) {
-        var tables = document.querySelectorAll('.markdown-section table');
-        var headings = document.querySelectorAll('.markdown-section h1, .markdown-section h2, .markdown-section h3, .markdown-section h4, .markdown-section h5, .markdown-section h6');
-
-        // Apply table styles
-        tables.forEach(function(table) {
-            var ths = table.querySelectorAll('th');
-            var tds = table.querySelectorAll('td');
-
-            if (themeName === 'night') {
-                // Night theme - apply dark styles
-                table.style.background = '#1c1c1c';
-                ths.forEach(function(th) {
-                    th.style.backgroundColor = '#2d2d2d';
-                    th.style.color = '#e8e8e8';
-                    th.style.borderColor = '#3a3a3a';
-                });
-                tds.forEach(function(td) {
-                    td.style.backgroundColor = '#1c1c1c';
-                    td.style.color = '#c8c8c8';
-                    td.style.borderColor = '#3a3a3a';
-                });
-            } else if (themeName === 'sepia') {
-                // Sepia theme
-                table.style.background = '';
-                ths.forEach(function(th) {
-                    th.style.backgroundColor = '#ebe4d0';
-                    th.style.color = '#5f4b32';
-                    th.style.borderColor = '#d4c9b0';
-                });
-                tds.forEach(function(td) {
-                    td.style.backgroundColor = '';
-                    td.style.color = '#5f4b32';
-                    td.style.borderColor = '#d4c9b0';
-                });
-            } else {
-                // White theme - clear inline styles (use CSS defaults)
-                table.style.background = '';
-                ths.forEach(function(th) {
-                    th.style.backgroundColor = '';
-                    th.style.color = '';
-                    th.style.borderColor = '';
-                });
-                tds.forEach(function(td) {
-                    td.style.backgroundColor = '';
-                    td.style.color = '';
-                    td.style.borderColor = '';
-                });
-            }
-        });
-
-        // Apply heading styles (h1-h6)
-        headings.forEach(function(heading) {
-            if (themeName === 'night') {
-                // Night theme - dark background for headings
-                heading.style.backgroundColor = '#2d2d2d';
-                heading.style.color = '#e8e8e8';
-                heading.style.borderColor = '#3a3a3a';
-                heading.style.paddingLeft = '12px';
-                heading.style.paddingRight = '12px';
-            } else if (themeName === 'sepia') {
-                // Sepia theme
-                heading.style.backgroundColor = '#ebe4d0';
-                heading.style.color = '#5f4b32';
-                heading.style.borderColor = '#d4c9b0';
-                heading.style.paddingLeft = '12px';
-                heading.style.paddingRight = '12px';
-            } else {
-                // White theme - clear inline styles
-                heading.style.backgroundColor = '';
-                heading.style.color = '';
-                heading.style.borderColor = '';
-                heading.style.paddingLeft = '';
-                heading.style.paddingRight = '';
-            }
-        });
     }
 
     // Update font size button states
@@ -218,7 +85,9 @@
     function init() {
         // Apply saved settings
         applyFontSize(getFontSizeIndex());
-        applyTheme(getTheme());
+        if (window.guidebookTheme) {
+            updateThemeButtons(window.guidebookTheme.getChoice());
+        }
 
         // Set up event listeners
         var toolbar = document.querySelector('.fontsettings-toolbar');
@@ -261,8 +130,6 @@
             var size = FONT_SIZES[getFontSizeIndex()];
             markdownSection.style.fontSize = size + 'px';
         }
-        // Re-apply table styles for current theme
-        applyTableStyles(getTheme());
     }
 
     // Initialize on DOM ready
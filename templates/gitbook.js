@@ -254,6 +254,9 @@
             // - Arrow click: collapsible.js calls stopImmediatePropagation(), so this handler won't run
             // - Text click: collapsible.js returns without stopping, so this handler runs for SPA navigation
 
+            // Language switcher links load a different book, so navigate normally
+            if (link.closest('.lang-switcher')) return;
+
             var href = link.getAttribute('href');
             if (!href || href.startsWith('#') || href.startsWith('http')) return;
 
@@ -308,6 +311,15 @@
                     currentContent.innerHTML = newContent.innerHTML;
                 }
 
+                // Update header/footer hook snippets (they may use the page title)
+                ['.page-hook-header', '.page-hook-footer'].forEach(function(selector) {
+                    var newHook = doc.querySelector(selector);
+                    var currentHook = document.querySelector(selector);
+                    if (newHook && currentHook) {
+                        currentHook.innerHTML = newHook.innerHTML;
+                    }
+                });
+
                 // Update title
                 var newTitle = doc.querySelector('title');
                 if (newTitle) {
@@ -320,7 +332,7 @@
                 });
 
                 // Find and mark new active item
-                var newActiveHref = url.replace(/^\.\//, '').split('#')[0];
+                var newActiveHref = url.replace(/^\.\//, '').split('#')[0].split('?')[0];
                 document.querySelectorAll('.book-summary .chapter a').forEach(function(link) {
                     var href = link.getAttribute('href');
                     if (href === newActiveHref || href === './' + newActiveHref) {
@@ -342,6 +354,15 @@
                 // Update URL (use absolute URL to avoid relative path issues with SPA navigation)
                 history.pushState(null, '', absoluteUrl);
 
+                // Update language switcher links to point at the new page's translations
+                // (hrefs in the fetched page are relative to its URL, which is now current)
+                var newLangSwitcher = doc.querySelector('.lang-switcher');
+                var currentLangSwitcher = document.querySelector('.lang-switcher');
+                if (newLangSwitcher && currentLangSwitcher) {
+                    currentLangSwitcher.innerHTML = newLangSwitcher.innerHTML;
+                    currentLangSwitcher.removeAttribute('open');
+                }
+
                 // Scroll to hash anchor or top
                 if (hash) {
                     try {
@@ -361,8 +382,10 @@
                     window.scrollTo(0, 0);
                 }
 
-                // Re-init mermaid if present
-                if (typeof mermaid !== 'undefined') {
+                // Render mermaid diagrams in the current color theme
+                if (window.guidebookTheme) {
+                    window.guidebookTheme.renderMermaid();
+                } else if (typeof mermaid !== 'undefined') {
                     mermaid.init(undefined, '.markdown-section .mermaid');
                 }
 
@@ -373,7 +396,7 @@
                     });
                 }
 
-                // Re-apply font settings (theme styles for tables/headings)
+                // Re-apply font settings
                 if (window.guidebookFontsettings && window.guidebookFontsettings.reapply) {
                     window.guidebookFontsettings.reapply();
                 }
@@ -436,6 +459,9 @@
                 // Reset navigation state
                 isNavigating = false;
                 document.body.classList.remove('loading');
+
+                // Let other scripts (e.g. search highlighting) process the new content
+                document.dispatchEvent(new CustomEvent('guidebook:page-loaded'));
             })
             .catch(function(err) {
                 console.error('Navigation error:', err);
@@ -447,7 +473,7 @@
 
     // Handle browser back/forward
     window.addEventListener('popstate', function() {
-        loadPage(location.pathname + location.hash, null);
+        loadPage(location.pathname + location.search + location.hash, null);
     });
 
     setupSpaNavigation();
@@ -481,6 +507,208 @@
         window.addEventListener('load', scrollToHashOnLoad);
     }
 
+    // Glossary popovers: a term's definition (a hidden element named by its
+    // aria-describedby) is shown next to it on hover and keyboard focus, and
+    // stays open while the pointer or focus is inside it. Event delegation
+    // keeps this working when SPA navigation replaces the page content.
+    function setupGlossaryPopovers() {
+        var open = null;
+        var hideTimer = null;
+
+        function termOf(node) {
+            return node && node.closest ? node.closest('.glossary-term[aria-describedby]') : null;
+        }
+
+        function popoverOf(node) {
+            return node && node.closest ? node.closest('.glossary-popover') : null;
+        }
+
+        function hide() {
+            clearTimeout(hideTimer);
+            if (open) {
+                open.popover.hidden = true;
+                open = null;
+            }
+        }
+
+        function hideSoon() {
+            clearTimeout(hideTimer);
+            hideTimer = setTimeout(hide, 200);
+        }
+
+        function show(term) {
+            var popover = document.getElementById(term.getAttribute('aria-describedby'));
+            if (!popover) return;
+            clearTimeout(hideTimer);
+            if (open && open.popover === popover) return;
+            hide();
+            popover.hidden = false;
+            open = { term: term, popover: popover };
+
+            // Below the term, or above it when there's no room; kept inside the viewport
+            var rect = term.getBoundingClientRect();
+            var margin = 8;
+            var width = popover.offsetWidth;
+            var height = popover.offsetHeight;
+            var start = document.documentElement.dir === 'rtl' ? rect.right - width : rect.left;
+            var left = Math.max(margin, Math.min(start, window.innerWidth - width - margin));
+            var top = rect.bottom + margin;
+            if (top + height > window.innerHeight && rect.top - height - margin > 0) {
+                top = rect.top - height - margin;
+            }
+            popover.style.left = left + 'px';
+            popover.style.top = top + 'px';
+        }
+
+        document.addEventListener('mouseover', function(e) {
+            var term = termOf(e.target);
+            if (term) {
+                show(term);
+            } else if (open && popoverOf(e.target) === open.popover) {
+                clearTimeout(hideTimer);
+            }
+        });
+        document.addEventListener('mouseout', function(e) {
+            if (open && (termOf(e.target) === open.term || popoverOf(e.target) === open.popover)) {
+                hideSoon();
+            }
+        });
+        document.addEventListener('focusin', function(e) {
+            var term = termOf(e.target);
+            if (term) {
+                show(term);
+            } else if (open && popoverOf(e.target) === open.popover) {
+                clearTimeout(hideTimer);
+            } else {
+                hide();
+            }
+        });
+        document.addEventListener('keydown', function(e) {
+            if (e.key === 'Escape' && open) {
+                var term = open.term;
+                var inside = open.popover.contains(document.activeElement);
+                hide();
+                if (inside) term.focus();
+            }
+        });
+        // The popover is placed for the current scroll position
+        window.addEventListener('scroll', hide, { passive: true });
+        document.addEventListener('guidebook:page-loaded', hide);
+    }
+
+    setupGlossaryPopovers();
+
+    // Tabs: selecting a tab selects the tab with the same title in every tab
+    // set, and the choice is remembered across pages (most recent first, so
+    // "macOS" and "Python" can both be remembered). Panels are switched with
+    // the hidden attribute; print styles show them all.
+    function setupTabs() {
+        var storageKey = 'guidebook-tabs';
+
+        function choices() {
+            try {
+                var stored = JSON.parse(localStorage.getItem(storageKey));
+                return Array.isArray(stored) ? stored : [];
+            } catch (ex) {
+                return [];
+            }
+        }
+
+        function tabsOf(tab) {
+            return Array.prototype.slice.call(tab.closest('[role="tablist"]').querySelectorAll('[role="tab"]'));
+        }
+
+        function select(tab) {
+            tabsOf(tab).forEach(function(other) {
+                var selected = other === tab;
+                other.setAttribute('aria-selected', selected ? 'true' : 'false');
+                other.tabIndex = selected ? 0 : -1;
+                var panel = document.getElementById(other.getAttribute('aria-controls'));
+                if (panel) panel.hidden = !selected;
+            });
+        }
+
+        // Select the most recently chosen title each tab set has
+        function restore() {
+            var titles = choices();
+            if (!titles.length) return;
+            document.querySelectorAll('.tabs [role="tablist"]').forEach(function(list) {
+                var tabs = Array.prototype.slice.call(list.querySelectorAll('[role="tab"]'));
+                for (var i = 0; i < titles.length; i++) {
+                    var match = tabs.filter(function(tab) { return tab.textContent === titles[i]; })[0];
+                    if (match) {
+                        select(match);
+                        return;
+                    }
+                }
+            });
+        }
+
+        function choose(tab) {
+            var title = tab.textContent;
+            var titles = choices().filter(function(other) { return other !== title; });
+            titles.unshift(title);
+            try {
+                localStorage.setItem(storageKey, JSON.stringify(titles.slice(0, 20)));
+            } catch (ex) {
+                // Storage may be unavailable (private mode); the choice applies to this page only
+            }
+            // Keep the chosen tab where it is while other tab sets above it change height
+            var top = tab.getBoundingClientRect().top;
+            select(tab);
+            restore();
+            window.scrollBy(0, tab.getBoundingClientRect().top - top);
+        }
+
+        // Show the tab holding the target of a link to an anchor inside a hidden panel
+        function revealHash() {
+            if (!window.location.hash) return;
+            var target;
+            try {
+                target = document.getElementById(decodeURIComponent(window.location.hash.substring(1)));
+            } catch (ex) {
+                return;
+            }
+            var panel = target && target.closest('.tab-panel[hidden]');
+            while (panel) {
+                var tab = document.getElementById(panel.getAttribute('aria-labelledby'));
+                if (tab) select(tab);
+                panel = panel.parentElement.closest('.tab-panel[hidden]');
+            }
+        }
+
+        document.addEventListener('click', function(e) {
+            var tab = e.target.closest && e.target.closest('.tabs [role="tab"]');
+            if (tab) choose(tab);
+        });
+        document.addEventListener('keydown', function(e) {
+            var tab = e.target.closest && e.target.closest('.tabs [role="tab"]');
+            if (!tab) return;
+            var tabs = tabsOf(tab);
+            var index = tabs.indexOf(tab);
+            var step = document.documentElement.dir === 'rtl' ? -1 : 1;
+            var next = null;
+            if (e.key === 'ArrowRight') next = tabs[(index + step + tabs.length) % tabs.length];
+            else if (e.key === 'ArrowLeft') next = tabs[(index - step + tabs.length) % tabs.length];
+            else if (e.key === 'Home') next = tabs[0];
+            else if (e.key === 'End') next = tabs[tabs.length - 1];
+            if (!next) return;
+            e.preventDefault();
+            choose(next);
+            next.focus();
+        });
+
+        restore();
+        revealHash();
+        window.addEventListener('hashchange', revealHash);
+        document.addEventListener('guidebook:page-loaded', function() {
+            restore();
+            revealHash();
+        });
+    }
+
+    setupTabs();
+
     // Initialize syntax highlighting on page load
     if (typeof hljs !== 'undefined') {
         hljs.highlightAll();
@@ -0,0 +1,49 @@
+// Root page of a multi-language book - loaded in <head> when book.json sets
+// "languageRedirect". Sends the reader to the language their browser prefers,
+// or to the default language when none matches. Readers coming back from a
+// page of the book see the language list instead.
+
+(function() {
+    'use strict';
+
+    // [{ "code": "pt", "lang": "pt-BR" }, ...] - languages that aren't hidden
+    var script = document.currentScript;
+    var languages = JSON.parse(script.getAttribute('data-languages'));
+    var fallback = script.getAttribute('data-default');
+
+    var base = location.href.replace(/[?#].*$/, '').replace(/[^\/]*$/, '');
+    if (document.referrer && document.referrer.indexOf(base) === 0) {
+        return;
+    }
+
+    function primary(tag) {
+        return tag.toLowerCase().split(/[-_]/)[0];
+    }
+
+    // Browser languages in order of preference, e.g. ["pt-BR", "en"]
+    var preferred = navigator.languages && navigator.languages.length
+        ? navigator.languages
+        : [navigator.language || ''];
+
+    function find(match) {
+        for (var i = 0; i < preferred.length; i++) {
+            for (var j = 0; j < languages.length; j++) {
+                if (match(preferred[i], languages[j].lang)) {
+                    return languages[j].code;
+                }
+            }
+        }
+        return null;
+    }
+
+    // An exact tag first ("pt-BR"), then the same language in any region ("pt")
+    var target = find(function(wanted, lang) {
+        return wanted.toLowerCase() === lang.toLowerCase();
+    }) || find(function(wanted, lang) {
+        return wanted !== '' && primary(wanted) === primary(lang);
+    }) || fallback;
+
+    if (target) {
+        location.replace(target + '/' + location.hash);
+    }
+})();
@@ -3,7 +3,6 @@
 (function() {
     'use strict';
 
-    var searchIndex = null;
     var searchInput = document.querySelector('.search-input');
     var searchResults = document.querySelector('.search-results');
     var searchWrapper = document.querySelector('.search-wrapper');
@@ -11,62 +10,321 @@
 
     if (!searchInput || !searchResults) return;
 
-    // Load search index
-    function loadSearchIndex() {
-        if (searchIndex) return Promise.resolve(searchIndex);
+    // Localized message rendered by the page template
+    var noResultsText = searchInput.getAttribute('data-no-results') || 'No results found';
 
-        var indexUrl = rootPath + 'search_index.json';
+    // A search index: this book's own, or one listed in search_library.json.
+    // base is the absolute URL of the directory holding its search_index.json
+    function createSource(base, book, label, language) {
+        return {
+            base: base,
+            book: book || '',
+            label: label || '',
+            language: language || '',
+            index: null,
+            indexPromise: null,
+            // Loaded term shards by key: { terms: sorted term list, postings: term → encoded postings }
+            shards: {}
+        };
+    }
+
+    var ownSource = createSource(new URL(rootPath || './', window.location.href).href);
+    var sourcesPromise = null;
 
-        return fetch(indexUrl)
+    // Load a source's manifest (entries and shard list; terms are loaded per shard)
+    function loadSearchIndex(source) {
+        if (source.indexPromise) return source.indexPromise;
+
+        source.indexPromise = fetch(source.base + 'search_index.json')
             .then(function(response) {
                 if (!response.ok) throw new Error('Search index not found');
                 return response.json();
             })
             .then(function(data) {
-                searchIndex = data;
-                return data;
+                source.index = {
+                    stem: data.stem,
+                    shards: data.shards,
+                    library: data.library,
+                    docs: data.docs.map(function(doc) {
+                        return {
+                            title: doc[0],
+                            path: doc[1],
+                            id: doc[2],
+                            breadcrumb: data.breadcrumbs[doc[3]],
+                            snippet: doc[4]
+                        };
+                    })
+                };
+                return source.index;
             })
             .catch(function(err) {
                 console.error('Failed to load search index:', err);
-                return [];
+                return null;
             });
+        return source.indexPromise;
     }
 
-    // Simple search function
-    function search(query) {
-        if (!searchIndex || !query) return [];
+    // Sources to search: this book alone, or every source of search_library.json
+    // (named by the search box on the language index page, or by this book's manifest)
+    function loadSources() {
+        if (sourcesPromise) return sourcesPromise;
 
-        var lowerQuery = query.toLowerCase();
-        var results = [];
+        var libraryAttr = searchInput.getAttribute('data-search-library');
+        var libraryUrl = libraryAttr
+            ? Promise.resolve(new URL(rootPath + libraryAttr, window.location.href).href)
+            : loadSearchIndex(ownSource).then(function(index) {
+                return index && index.library ? new URL(index.library, ownSource.base).href : null;
+            });
 
-        searchIndex.forEach(function(entry) {
-            var titleMatch = entry.title.toLowerCase().indexOf(lowerQuery);
-            var contentMatch = entry.content.toLowerCase().indexOf(lowerQuery);
+        sourcesPromise = libraryUrl.then(function(url) {
+            if (!url) return [ownSource];
+            return loadLibrary(url).catch(function(err) {
+                console.error('Failed to load search library:', err);
+                return libraryAttr ? [] : [ownSource];
+            });
+        });
+        return sourcesPromise;
+    }
 
-            if (titleMatch !== -1 || contentMatch !== -1) {
-                var score = 0;
-                if (titleMatch !== -1) score += 10;
-                if (contentMatch !== -1) score += 1;
+    // Read search_library.json; the current book comes first
+    function loadLibrary(url) {
+        return fetch(url)
+            .then(function(response) {
+                if (!response.ok) throw new Error('Search library not found');
+                return response.json();
+            })
+            .then(function(data) {
+                var sources = data.sources.map(function(entry) {
+                    var base = new URL(entry.url || './', url).href;
+                    if (base === ownSource.base) {
+                        ownSource.book = entry.book;
+                        ownSource.label = entry.label;
+                        ownSource.language = entry.language;
+                        return ownSource;
+                    }
+                    return createSource(base, entry.book, entry.label, entry.language);
+                });
+                sources.sort(function(a, b) {
+                    return (b === ownSource) - (a === ownSource);
+                });
+                return sources;
+            });
+    }
+
+    // Shard key of a term - must match shard_key in src/builder/search.rs
+    function shardKey(term) {
+        var first = String.fromCodePoint(term.codePointAt(0));
+        if (/^[a-z0-9]$/.test(first)) return first;
+        var hex = (term.codePointAt(0) % 64).toString(16);
+        return 'u' + (hex.length < 2 ? '0' + hex : hex);
+    }
+
+    // Load a source's term shards (cached); missing shards have no terms
+    function loadShards(source, keys) {
+        var shards = source.shards;
+        return Promise.all(keys.map(function(key) {
+            if (shards[key]) return shards[key];
+            if (source.index.shards.indexOf(key) === -1) {
+                shards[key] = Promise.resolve({ terms: [], postings: {} });
+                return shards[key];
+            }
+
+            shards[key] = fetch(source.base + 'search_index/' + key + '.json')
+                .then(function(response) {
+                    if (!response.ok) throw new Error('Search shard not found');
+                    return response.json();
+                })
+                .then(function(postings) {
+                    // Sorted terms allow prefix lookups with a binary search
+                    return { terms: Object.keys(postings).sort(), postings: postings };
+                })
+                .catch(function(err) {
+                    console.error('Failed to load search shard:', err);
+                    delete shards[key];
+                    return { terms: [], postings: {} };
+                });
+            return shards[key];
+        }));
+    }
+
+    // Shards needed for a query: the shard of each token. A single CJK
+    // character is indexed on its own, so it needs only its own shard too
+    function shardsForTokens(tokens) {
+        var keys = [];
+        tokens.forEach(function(token) {
+            var key = shardKey(token);
+            if (keys.indexOf(key) === -1) keys.push(key);
+        });
+        return keys;
+    }
 
-                // Extract snippet around match
-                var snippet = '';
-                if (contentMatch !== -1) {
-                    var start = Math.max(0, contentMatch - 50);
-                    var end = Math.min(entry.content.length, contentMatch + query.length + 50);
-                    snippet = (start > 0 ? '...' : '') +
-                              entry.content.substring(start, end) +
-                              (end < entry.content.length ? '...' : '');
+    // Decode delta-encoded postings [doc delta, score, ...] into [[doc, score], ...]
+    function decodePostings(encoded) {
+        var postings = [];
+        var doc = 0;
+        for (var i = 0; i + 1 < encoded.length; i += 2) {
+            doc += encoded[i];
+            postings.push([doc, encoded[i + 1]]);
+        }
+        return postings;
+    }
+
+    // Tokenizer - must match Tokenizer in src/builder/search.rs
+    var CJK_CHAR = /[\u3040-\u30ff\u3400-\u4dbf\u4e00-\u9fff\uf900-\ufaff\uff66-\uff9f\uac00-\ud7af]/;
+    var WORD_CHAR = /[\p{Alphabetic}\p{N}]/u;
+
+    function tokenize(text, stem) {
+        var tokens = [];
+        var word = '';
+        var cjkRun = [];
+
+        function flushWord() {
+            if (word) tokens.push(stem ? stemEnglish(word) : word);
+            word = '';
+        }
+
+        function flushCjk() {
+            if (cjkRun.length === 1) {
+                tokens.push(cjkRun[0]);
+            } else {
+                for (var i = 0; i + 1 < cjkRun.length; i++) {
+                    tokens.push(cjkRun[i] + cjkRun[i + 1]);
                 }
+            }
+            cjkRun = [];
+        }
+
+        Array.from(text).forEach(function(c) {
+            if (CJK_CHAR.test(c)) {
+                flushWord();
+                cjkRun.push(c);
+            } else if (WORD_CHAR.test(c)) {
+                flushCjk();
+                word += c.toLowerCase();
+            } else {
+                flushWord();
+                flushCjk();
+            }
+        });
+        flushWord();
+        flushCjk();
+
+        return tokens;
+    }
+
+    function stemEnglish(word) {
+        if (word.length <= 3 || /[^\x00-\x7f]/.test(word)) return word;
+
+        var stem = word;
+        if (/sses$/.test(stem)) {
+            stem = stem.slice(0, -2);
+        } else if (/ies$/.test(stem)) {
+            stem = stem.slice(0, -3) + 'y';
+        } else if (/s$/.test(stem) && !/(ss|us|is)$/.test(stem)) {
+            stem = stem.slice(0, -1);
+        }
+
+        if (/ing$/.test(stem) && stem.length >= 6) {
+            stem = undouble(stem.slice(0, -3));
+        } else if (/ed$/.test(stem) && stem.length >= 5) {
+            stem = undouble(stem.slice(0, -2));
+        }
+
+        if (/e$/.test(stem) && stem.length > 4) {
+            stem = stem.slice(0, -1);
+        }
+
+        return stem;
+    }
+
+    function undouble(stem) {
+        var last = stem.charAt(stem.length - 1);
+        if (stem.length >= 2 && last === stem.charAt(stem.length - 2) && 'aeioulsz'.indexOf(last) === -1) {
+            return stem.slice(0, -1);
+        }
+        return stem;
+    }
 
-                results.push({
-                    title: entry.title,
-                    path: entry.path,
-                    snippet: snippet,
-                    score: score
+    // Terms of a loaded shard matching a query token: the exact term and terms
+    // it is a prefix of (search as you type). Returns [{term, weight}]
+    function matchingTerms(shard, token) {
+        var terms = shard.terms;
+        var matches = [];
+
+        // First term >= token
+        var lo = 0, hi = terms.length;
+        while (lo < hi) {
+            var mid = (lo + hi) >> 1;
+            if (terms[mid] < token) lo = mid + 1; else hi = mid;
+        }
+        for (var i = lo; i < terms.length && terms[i].indexOf(token) === 0 && matches.length < 50; i++) {
+            matches.push({ term: terms[i], weight: terms[i] === token ? 1 : 0.5 });
+        }
+
+        return matches;
+    }
+
+    // Search a source's inverted index: pages must match every query token.
+    // Resolves to the results once the shards the query needs are loaded
+    function search(source, query) {
+        var index = source.index;
+        if (!index || !query) return Promise.resolve([]);
+
+        var tokens = tokenize(query, index.stem).filter(function(token, i, all) {
+            return all.indexOf(token) === i;
+        });
+        if (tokens.length === 0) return Promise.resolve([]);
+
+        var keys = shardsForTokens(tokens);
+        return loadShards(source, keys).then(function(loaded) {
+            var byKey = {};
+            keys.forEach(function(key, i) {
+                byKey[key] = loaded[i];
+            });
+            return rankResults(source, tokens, function(token) {
+                return [byKey[shardKey(token)]];
+            });
+        });
+    }
+
+    // Score entries matching every token, using the shards returned by shardsOf(token)
+    function rankResults(source, tokens, shardsOf) {
+        var totals = null;
+        tokens.forEach(function(token) {
+            var tokenScores = {};
+            shardsOf(token).forEach(function(shard) {
+                matchingTerms(shard, token).forEach(function(match) {
+                    decodePostings(shard.postings[match.term]).forEach(function(posting) {
+                        var score = posting[1] * match.weight;
+                        tokenScores[posting[0]] = Math.max(tokenScores[posting[0]] || 0, score);
+                    });
+                });
+            });
+
+            if (totals === null) {
+                totals = tokenScores;
+            } else {
+                var merged = {};
+                Object.keys(totals).forEach(function(doc) {
+                    if (tokenScores[doc]) merged[doc] = totals[doc] + tokenScores[doc];
                 });
+                totals = merged;
             }
         });
 
+        var results = Object.keys(totals).map(function(doc) {
+            var entry = source.index.docs[doc];
+            return {
+                source: source,
+                title: entry.title,
+                path: entry.path,
+                id: entry.id,
+                breadcrumb: entry.breadcrumb || [],
+                snippet: entry.snippet,
+                score: totals[doc]
+            };
+        });
+
         // Sort by score (higher first)
         results.sort(function(a, b) {
             return b.score - a.score;
@@ -75,35 +333,129 @@
         return results.slice(0, 10); // Limit to 10 results
     }
 
-    // Render search results
-    function renderResults(results, query) {
-        if (results.length === 0) {
-            searchResults.innerHTML = '<div class="search-no-results">No results found</div>';
+    // Lowercased words of the query, used for snippets and highlighting
+    function queryWords(query) {
+        return query.toLowerCase().split(/\s+/).filter(function(word) {
+            return word.length > 0;
+        });
+    }
+
+    // Search every source, resolving to [{source, results}] in source order
+    function searchAll(query) {
+        return loadSources().then(function(sources) {
+            return Promise.all(sources.map(function(source) {
+                return loadSearchIndex(source).then(function() {
+                    return search(source, query);
+                }).then(function(results) {
+                    return { source: source, results: results };
+                });
+            }));
+        });
+    }
+
+    // Link to a result's section, carrying the query so the page can highlight it.
+    // Other books and languages get absolute links, so they load as full pages
+    function resultUrl(result, query) {
+        var base = result.source === ownSource ? rootPath : result.source.base;
+        var url = base + result.path + '?highlight=' + encodeURIComponent(queryWords(query).join(' '));
+        if (result.id) url += '#' + encodeURIComponent(result.id);
+        return url;
+    }
+
+    // Render search results, grouped by book and language when searching a library
+    function renderResults(groups, query) {
+        groups = groups.filter(function(group) {
+            return group.results.length > 0;
+        });
+        if (groups.length === 0) {
+            searchResults.innerHTML = '<div class="search-no-results">' + escapeHtml(noResultsText) + '</div>';
             return;
         }
 
-        var html = results.map(function(result) {
+        var grouped = groups.length > 1 || groups[0].source !== ownSource;
+        searchResults.innerHTML = groups.map(function(group) {
+            var items = renderItems(group.results, query);
+            if (!grouped) return items;
+
+            var source = group.source;
+            var name = [source.book, source.label].filter(Boolean).join(' · ');
+            var lang = source.language ? ' lang="' + escapeHtml(source.language) + '"' : '';
+            return '<div class="search-result-group" role="group"' + lang +
+                   (name ? ' aria-label="' + escapeHtml(name) + '"' : '') + '>' +
+                   (name ? '<div class="search-result-group-title">' + escapeHtml(name) + '</div>' : '') +
+                   items + '</div>';
+        }).join('');
+    }
+
+    function renderItems(results, query) {
+        return results.map(function(result) {
             var highlightedTitle = highlightMatch(result.title, query);
             var highlightedSnippet = result.snippet ? highlightMatch(result.snippet, query) : '';
 
-            return '<a class="search-result-item" href="' + rootPath + result.path + '">' +
+            var breadcrumb = result.breadcrumb.map(escapeHtml).join(' &rsaquo; ');
+
+            return '<a class="search-result-item" href="' + escapeHtml(resultUrl(result, query)) + '">' +
+                   (breadcrumb ? '<div class="search-result-breadcrumb">' + breadcrumb + '</div>' : '') +
                    '<div class="search-result-title">' + highlightedTitle + '</div>' +
                    (highlightedSnippet ? '<div class="search-result-snippet">' + highlightedSnippet + '</div>' : '') +
                    '</a>';
         }).join('');
-
-        searchResults.innerHTML = html;
     }
 
-    // Highlight matching text
+    // Highlight query words in text
     function highlightMatch(text, query) {
-        if (!query) return escapeHtml(text);
+        var words = queryWords(query).map(function(word) {
+            return escapeRegex(escapeHtml(word));
+        });
+        if (words.length === 0) return escapeHtml(text);
 
         var escaped = escapeHtml(text);
-        var regex = new RegExp('(' + escapeRegex(query) + ')', 'gi');
+        var regex = new RegExp('(' + words.join('|') + ')', 'gi');
         return escaped.replace(regex, '<mark>$1</mark>');
     }
 
+    // Highlight the words of ?highlight= (set by search result links) in the page content
+    function highlightFromUrl() {
+        var param = new URLSearchParams(window.location.search).get('highlight');
+        var content = document.querySelector('.markdown-section');
+        if (!param || !content) return;
+
+        var words = queryWords(param).map(escapeRegex);
+        if (words.length === 0) return;
+        var regex = new RegExp(words.join('|'), 'gi');
+
+        var walker = document.createTreeWalker(content, NodeFilter.SHOW_TEXT, {
+            acceptNode: function(node) {
+                var parent = node.parentNode;
+                if (parent.closest('script, style, mark, .mermaid')) return NodeFilter.FILTER_REJECT;
+                return NodeFilter.FILTER_ACCEPT;
+            }
+        });
+        var nodes = [];
+        while (walker.nextNode()) nodes.push(walker.currentNode);
+
+        nodes.forEach(function(node) {
+            var text = node.nodeValue;
+            regex.lastIndex = 0;
+            if (!regex.test(text)) return;
+
+            var fragment = document.createDocumentFragment();
+            var last = 0;
+            regex.lastIndex = 0;
+            text.replace(regex, function(match, offset) {
+                fragment.appendChild(document.createTextNode(text.substring(last, offset)));
+                var mark = document.createElement('mark');
+                mark.className = 'search-highlight';
+                mark.textContent = match;
+                fragment.appendChild(mark);
+                last = offset + match.length;
+                return match;
+            });
+            fragment.appendChild(document.createTextNode(text.substring(last)));
+            node.parentNode.replaceChild(fragment, node);
+        });
+    }
+
     function escapeHtml(text) {
         var div = document.createElement('div');
         div.textContent = text;
@@ -128,9 +480,10 @@
         }
 
         debounceTimer = setTimeout(function() {
-            loadSearchIndex().then(function() {
-                var results = search(query);
-                renderResults(results, query);
+            searchAll(query).then(function(groups) {
+                // Ignore results of a query the user has already changed
+                if (searchInput.value.trim() !== query) return;
+                renderResults(groups, query);
                 searchResults.classList.add('visible');
             });
         }, 200);
@@ -157,8 +510,12 @@
         }
     });
 
+    // Highlight search terms on arrival, including pages loaded by SPA navigation
+    highlightFromUrl();
+    document.addEventListener('guidebook:page-loaded', highlightFromUrl);
+
     // Preload search index on first focus
     searchInput.addEventListener('focus', function() {
-        loadSearchIndex();
+        loadSources();
     }, { once: true });
 })();
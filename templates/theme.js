@@ -0,0 +1,150 @@
+// Color themes - loaded in <head> so the page is painted in the right theme.
+// Picks the reader's saved choice, then the book default, then the system
+// setting ("auto"), and switches highlight.js and Mermaid styles with it.
+
+(function() {
+    'use strict';
+
+    var STORAGE_KEY_THEME = 'guidebook-theme';
+    var AUTO = 'auto';
+
+    // { "default": "auto", "themes": { "white": { "dark": false, "mermaid": "default" }, ... } }
+    var script = document.currentScript;
+    var config = JSON.parse(script.getAttribute('data-themes'));
+    var root = document.documentElement;
+    var darkQuery = window.matchMedia ? window.matchMedia('(prefers-color-scheme: dark)') : null;
+    var current = null;
+
+    function isKnown(name) {
+        return name === AUTO || Object.prototype.hasOwnProperty.call(config.themes, name);
+    }
+
+    // The reader's choice ("auto" or a theme name), falling back to the book default
+    function getChoice() {
+        var stored = null;
+        try {
+            stored = localStorage.getItem(STORAGE_KEY_THEME);
+        } catch (e) {
+            // Storage may be unavailable (private mode, file://)
+        }
+        if (stored && isKnown(stored)) {
+            return stored;
+        }
+        return isKnown(config.default) ? config.default : AUTO;
+    }
+
+    // Theme actually shown for a choice
+    function resolve(choice) {
+        if (choice === AUTO) {
+            return darkQuery && darkQuery.matches ? 'night' : 'white';
+        }
+        return choice;
+    }
+
+    // Keep the theme-<name> class on .book for stylesheets written against it
+    function updateBookClass() {
+        var book = document.querySelector('.book');
+        if (!book || !current) return;
+        Object.keys(config.themes).forEach(function(name) {
+            book.classList.remove('theme-' + name);
+        });
+        book.classList.add('theme-' + current);
+    }
+
+    function updateHighlightStyles() {
+        document.querySelectorAll('link.hljs-theme').forEach(function(link) {
+            var themes = (link.getAttribute('data-themes') || '').split(' ');
+            link.disabled = themes.indexOf(current) === -1;
+        });
+    }
+
+    // Render Mermaid diagrams in the current theme. Sources are kept in
+    // data-source so diagrams can be drawn again when the theme changes.
+    function renderMermaid(rerender) {
+        if (typeof mermaid === 'undefined' || !current) return;
+
+        var nodes = [];
+        document.querySelectorAll('.mermaid').forEach(function(el) {
+            if (!el.hasAttribute('data-source')) {
+                if (el.getAttribute('data-processed')) return;
+                el.setAttribute('data-source', el.textContent);
+            } else if (rerender) {
+                el.removeAttribute('data-processed');
+                el.textContent = el.getAttribute('data-source');
+            } else if (el.getAttribute('data-processed')) {
+                return;
+            }
+            nodes.push(el);
+        });
+        if (nodes.length === 0) return;
+
+        mermaid.initialize({ startOnLoad: false, theme: config.themes[current].mermaid });
+        if (typeof mermaid.run === 'function') {
+            mermaid.run({ nodes: nodes });
+        } else {
+            mermaid.init(undefined, nodes);
+        }
+    }
+
+    function apply(choice) {
+        var theme = resolve(choice);
+        var changed = current !== null && current !== theme;
+        current = theme;
+
+        root.setAttribute('data-theme', theme);
+        root.setAttribute('data-color-scheme', config.themes[theme].dark ? 'dark' : 'light');
+        updateBookClass();
+        updateHighlightStyles();
+
+        if (changed) {
+            renderMermaid(true);
+            document.dispatchEvent(new CustomEvent('guidebook:theme-changed', {
+                detail: { choice: choice, theme: theme }
+            }));
+        }
+    }
+
+    // Called by the theme buttons; "auto" forgets the reader's choice
+    function select(choice) {
+        if (!isKnown(choice)) return;
+        try {
+            if (choice === AUTO) {
+                localStorage.removeItem(STORAGE_KEY_THEME);
+            } else {
+                localStorage.setItem(STORAGE_KEY_THEME, choice);
+            }
+        } catch (e) {
+            // Still switch for this page view
+        }
+        apply(choice);
+    }
+
+    apply(getChoice());
+
+    // Follow the system setting while the reader hasn't picked a theme
+    if (darkQuery) {
+        var onSystemChange = function() {
+            if (getChoice() === AUTO) {
+                apply(AUTO);
+            }
+        };
+        if (darkQuery.addEventListener) {
+            darkQuery.addEventListener('change', onSystemChange);
+        } else if (darkQuery.addListener) {
+            darkQuery.addListener(onSystemChange);
+        }
+    }
+
+    document.addEventListener('DOMContentLoaded', function() {
+        updateBookClass();
+        renderMermaid(false);
+    });
+
+    window.guidebookTheme = {
+        getChoice: getChoice,
+        getTheme: function() { return current; },
+        select: select,
+        renderMermaid: function() { renderMermaid(false); }
+    };
+
+})();